@@ -1,11 +1,34 @@
-use anyhow::{Context, Result};
-use std::{io, time::Duration};
+use anyhow::{bail, Context, Result};
+#[cfg(unix)]
+use ratatui::{
+    backend::CrosstermBackend,
+    crossterm::{
+        cursor, execute,
+        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    Terminal,
+};
+#[cfg(unix)]
+use std::{
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+};
+use std::{
+    io::{self, IsTerminal, Write},
+    time::Duration,
+};
 
 use crate::{
-    config::{Config, TomlColor},
+    config::{Config, NonTtyMode, Preset, TomlColor},
+    pomodoro::Pomodoro,
+    song,
     state::App,
 };
-use clap::{Arg, ArgAction, ArgGroup, Command};
+use clap::{error::ErrorKind, parser::ValueSource, Arg, ArgAction, ArgGroup, ArgMatches, Command};
+
+// The maximum countdown duration, which prevents overflows with absurd time arguments
+const MAX_HOURS: u64 = 99;
+const MAX_SECS: u64 = MAX_HOURS * 3600;
 
 // A time unit
 #[derive(Clone)]
@@ -22,6 +45,32 @@ struct TimeValue {
     unit: TimeUnit,
 }
 
+impl TimeUnit {
+    // How many seconds a single unit has
+    fn secs(&self) -> u64 {
+        match self {
+            TimeUnit::Hours => 3600,
+            TimeUnit::Minutes => 60,
+            TimeUnit::Seconds => 1,
+        }
+    }
+}
+
+impl TimeValue {
+    // Convert the value to a Duration, the parser makes sure this doesn't overflow
+    fn to_duration(&self) -> Duration {
+        Duration::from_secs(self.value * self.unit.secs())
+    }
+}
+
+// A part of the pomodoro argument, like "work:25m"
+#[derive(Clone)]
+enum PomodoroValue {
+    Work(Duration),
+    Break(Duration),
+    Cycles(u32),
+}
+
 // Parse CLI arguments
 pub fn parse() -> Result<()> {
     // Create the app and load the config file
@@ -30,24 +79,210 @@ pub fn parse() -> Result<()> {
 
     let matches = command(&config).get_matches();
 
+    // Load the config again on top of the selected preset
+    if let Some(&preset) = matches.get_one::<Preset>("preset") {
+        config = Config::load_with_preset(Some(preset))?;
+    }
+
+    // Report the problems of the config file, which can't be shown once the TUI is running
+    for warning in config.take_warnings() {
+        eprintln!("Warning: {}", warning);
+    }
+
+    // Reject conflicting inputs which clap can't detect on its own
+    if let Err(e) = check_pomodoro_conflicts(&config, &matches) {
+        e.exit();
+    }
+
     // Check CLI arguments and update the config if necessary
 
     if matches.get_flag("dir") {
         return output_dir();
     }
 
+    if matches.get_flag("list-players") {
+        return output_players();
+    }
+
+    // Override the config file values with the provided CLI arguments
+    apply_matches(&mut config, &matches);
+
+    // Use the clipboard contents as text, unless an explicit text was provided. The clipboard is
+    // only read once, so a config reload doesn't replace the text with its current contents.
+    #[cfg(feature = "clipboard")]
+    let clipboard = (matches.get_flag("text-from-clipboard") && !matches.contains_id("text"))
+        .then(clipboard_text)
+        .flatten();
+    #[cfg(not(feature = "clipboard"))]
+    let clipboard: Option<String> = None;
+
+    if let Some(text) = &clipboard {
+        config.set_text(text.clone());
+    }
+
+    // Fall back to the last used channel if no channel was provided
+    config.apply_last_channel();
+
+    // Handle time parsing from command-line, unless the timer is disabled
+    let mut duration = matches
+        .get_many::<TimeValue>("time")
+        .filter(|_| !config.is_no_timer())
+        .map(|args| args.map(TimeValue::to_duration).sum::<Duration>());
+
+    // Use the duration of a timer preset from the config, instead of the time arguments
+    if let Some(name) = matches
+        .get_one::<String>("timer-preset")
+        .filter(|_| !config.is_no_timer())
+    {
+        duration = Some(preset_duration(&config, name));
+    }
+
+    if let Some(total) = duration {
+        // Every argument is below the maximum, but all of them combined might not be
+        if total.as_secs() > MAX_SECS {
+            command(&config)
+                .error(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "the time arguments exceed the maximum of {} hours",
+                        MAX_HOURS
+                    ),
+                )
+                .exit();
+        }
+    }
+
+    // Ask for the remaining settings, the timer is only asked for if it isn't disabled
+    if matches.get_flag("interactive") {
+        let ask_duration = !config.is_no_timer()
+            && !matches.contains_id("pomodoro")
+            && !matches.get_flag("stopwatch");
+        duration = prompt_settings(&mut config, duration, ask_duration)?;
+    }
+
+    // The values which are still needed once the config was handed to the App
+    let no_timer = config.is_no_timer();
+    let start_delay = config.get_start_delay();
+    let persist_timer = config.is_persist_timer();
+    let non_tty = config.get_non_tty();
+
+    // Apply changes to the config file while running. Values entered interactively only live in
+    // the current config, so they would be replaced by the file values on reload.
+    if config.is_hot_reload() && !matches.get_flag("interactive") {
+        app.watch_config(matches.clone(), config.get_twitch_channel(), clipboard);
+    }
+
+    // Set configuration for the app before the countdown, so it uses the configured clock source
+    app.set_config(config);
+
+    if let Some(duration) = duration {
+        app.set_duration(duration, start_delay);
+    }
+
+    // Handle the pomodoro argument, unless the timer is disabled
+    if let Some(args) = matches
+        .get_many::<PomodoroValue>("pomodoro")
+        .filter(|_| !no_timer)
+    {
+        let mut work = Duration::from_secs(25 * 60);
+        let mut rest = Duration::from_secs(5 * 60);
+        let mut cycles = 4;
+
+        for value in args {
+            match value {
+                PomodoroValue::Work(duration) => work = *duration,
+                PomodoroValue::Break(duration) => rest = *duration,
+                PomodoroValue::Cycles(count) => cycles = *count,
+            }
+        }
+
+        app.set_pomodoro(Pomodoro::new(work, rest, cycles), start_delay);
+    }
+
+    // Count the elapsed time up instead, unless the timer is disabled
+    if matches.get_flag("stopwatch") && !no_timer {
+        app.set_stopwatch(start_delay);
+    }
+
+    // Resume the countdown of the last run, unless another duration was provided
+    if persist_timer && !no_timer && duration.is_none() && !matches.contains_id("duration") {
+        app.restore_timer();
+    }
+
+    // Report the countdown over HTTP if a port was provided
+    if let Some(&port) = matches.get_one::<u16>("serve") {
+        app.set_serve_port(port);
+    }
+
+    // Collect render loop statistics if perf mode is enabled
+    if matches.get_flag("perf") {
+        app.enable_perf();
+    }
+
+    // The TUI can't be displayed if stdout isn't a terminal, unless it is rendered elsewhere
+    #[cfg(unix)]
+    let renders_to_stdout = !matches.contains_id("tty");
+    #[cfg(not(unix))]
+    let renders_to_stdout = true;
+
+    let headless = renders_to_stdout && !io::stdout().is_terminal();
+
+    // Only count down in the background without the TUI
+    if matches.get_flag("background") {
+        return app.run_background(!headless);
+    }
+
+    if headless {
+        return match non_tty {
+            NonTtyMode::Error => bail!(
+                "stdout is not a terminal, set 'non_tty = \"headless\"' to print the remaining time instead"
+            ),
+            NonTtyMode::Headless => app.run_background(false),
+        };
+    }
+
+    // Render to another terminal device if one was provided
+    #[cfg(unix)]
+    if let Some(tty) = matches.get_one::<PathBuf>("tty") {
+        run_app_on_tty(&mut app, tty)?;
+    } else {
+        run_app(&mut app)?;
+    }
+
+    #[cfg(not(unix))]
+    run_app(&mut app)?;
+
+    // Print the collected statistics after the terminal was restored
+    if let Some(stats) = app.get_perf_stats() {
+        println!("{}", stats);
+    }
+
+    Ok(())
+}
+
+// Override the config values with the provided CLI arguments, which take precedence over the
+// config file and the defaults
+pub fn apply_matches(config: &mut Config, matches: &ArgMatches) {
     if let Some(text) = matches.get_one::<String>("text") {
         config.set_text(text.clone());
     }
 
-    if let Some(&chat) = matches.get_one::<bool>("chat") {
+    if let Some(chat) = command_line_flag(matches, "chat") {
         config.set_chat(chat);
     }
 
-    if let Some(&song_display) = matches.get_one::<bool>("song-display") {
+    if matches.get_flag("no-chat") {
+        config.set_chat(false);
+    }
+
+    if let Some(song_display) = command_line_flag(matches, "song-display") {
         config.set_song_display(song_display);
     }
 
+    if matches.get_flag("no-song-display") {
+        config.set_song_display(false);
+    }
+
     if let Some(twitch) = matches.get_one::<String>("twitch") {
         config.set_twitch_channel(twitch.clone());
     }
@@ -56,42 +291,51 @@ pub fn parse() -> Result<()> {
         config.set_color(color.clone());
     }
 
-    if let Some(&hide_timer) = matches.get_one::<bool>("hide-timer") {
+    if let Some(hide_timer) = command_line_flag(matches, "hide-timer") {
         config.set_hide_timer(hide_timer);
     }
 
-    if let Some(&progress_bar) = matches.get_one::<bool>("progress-bar") {
+    if matches.get_flag("no-hide-timer") {
+        config.set_hide_timer(false);
+    }
+
+    if let Some(progress_bar) = command_line_flag(matches, "progress-bar") {
         config.set_progress_bar(progress_bar);
     }
 
+    if matches.get_flag("no-progress-bar") {
+        config.set_progress_bar(false);
+    }
+
     if let Some(&padding) = matches.get_one::<u16>("padding") {
         config.set_padding(padding);
     }
 
-    // Handle time parsing from command-line
-    if let Some(args) = matches.get_many::<TimeValue>("time") {
-        let mut total_secs: u32 = 0;
-
-        for TimeValue { value, unit } in args {
-            let secs = match unit {
-                TimeUnit::Hours => value * 3600,
-                TimeUnit::Minutes => value * 60,
-                TimeUnit::Seconds => *value,
-            };
-            total_secs += secs as u32;
-        }
+    if matches.get_flag("no-timer") {
+        config.set_no_timer(true);
+    }
 
-        app.set_duration(Duration::new(total_secs as u64, 0));
+    if matches.get_flag("clock") {
+        config.set_show_clock(true);
     }
+}
 
-    // Set configuration for the app and run it
-    app.set_config(config);
-    run_app(app)
+// Get the value of a bool argument, only if it was provided on the command line. Its default value
+// is taken from the config the command was built with, which would override a preset or reload.
+fn command_line_flag(matches: &ArgMatches, id: &str) -> Option<bool> {
+    if matches.value_source(id) == Some(ValueSource::CommandLine) {
+        matches.get_one::<bool>(id).copied()
+    } else {
+        None
+    }
 }
 
 // Constructs the CLI arguments
 fn command(config: &Config) -> Command {
-    Command::new("brb")
+    // Percentage time arguments are relative to the session length
+    let session_length = config.get_session_length();
+
+    let command = Command::new("brb")
         .version(env!("CARGO_PKG_VERSION"))
         .group(ArgGroup::new("customize").multiple(true))
         .next_help_heading("Customize")
@@ -107,19 +351,39 @@ fn command(config: &Config) -> Command {
             Arg::new("chat")
                 .long("chat")
                 .value_parser(clap::value_parser!(bool))
+                .num_args(0..=1)
+                .default_missing_value("true")
                 .default_value(if config.is_chat() { "true" } else { "false" })
                 .help("Show the chat")
+                .overrides_with("no-chat")
+                .group("customize"),
+            // Disable the chat
+            Arg::new("no-chat")
+                .long("no-chat")
+                .action(ArgAction::SetTrue)
+                .help("Hide the chat")
+                .overrides_with("chat")
                 .group("customize"),
             // Enable/disable current song display
             Arg::new("song-display")
                 .long("song-display")
                 .value_parser(clap::value_parser!(bool))
+                .num_args(0..=1)
+                .default_missing_value("true")
                 .default_value(if config.is_song_display() {
                     "true"
                 } else {
                     "false"
                 })
                 .help("Show the current song using spotic")
+                .overrides_with("no-song-display")
+                .group("customize"),
+            // Disable current song display
+            Arg::new("no-song-display")
+                .long("no-song-display")
+                .action(ArgAction::SetTrue)
+                .help("Hide the current song")
+                .overrides_with("song-display")
                 .group("customize"),
             // Set the twitch channel
             Arg::new("twitch")
@@ -127,35 +391,65 @@ fn command(config: &Config) -> Command {
                 .action(ArgAction::Set)
                 .help("The Twitch channel for chat integration")
                 .group("customize"),
+            // Use a preset as the base of the config
+            Arg::new("preset")
+                .long("preset")
+                .action(ArgAction::Set)
+                .value_parser(preset_arg_parser)
+                .help(
+                    "Use a preset as the base of the config, either 'minimal', 'full' or 'overlay'",
+                )
+                .value_name("PRESET")
+                .group("customize"),
             // Color argument, either color name or RGB value
             Arg::new("color")
                 .long("color")
                 .action(ArgAction::Set)
                 .value_parser(color_arg_parser)
-                .help("The accent color, either NAME like 'red' or RGB like '255,0,0'")
-                .value_name("NAME | RGB")
+                .help("The accent color, either NAME like 'orange', INDEX of the 256 colors like '208', RGB like '255,0,0' or HEX like '#ff0000'")
+                .value_name("NAME | INDEX | RGB | HEX")
                 .group("customize"),
             // Hide the timer after time is up
             Arg::new("hide-timer")
                 .long("hide-timer")
                 .value_parser(clap::value_parser!(bool))
+                .num_args(0..=1)
+                .default_missing_value("true")
                 .default_value(if config.is_hide_timer() {
                     "true"
                 } else {
                     "false"
                 })
                 .help("Hide the timer when it is finished")
+                .overrides_with("no-hide-timer")
+                .group("customize"),
+            // Keep the timer after time is up
+            Arg::new("no-hide-timer")
+                .long("no-hide-timer")
+                .action(ArgAction::SetTrue)
+                .help("Keep the timer visible when it is finished")
+                .overrides_with("hide-timer")
                 .group("customize"),
             // Enable/disable the progress bar
             Arg::new("progress-bar")
                 .long("progress-bar")
                 .value_parser(clap::value_parser!(bool))
+                .num_args(0..=1)
+                .default_missing_value("true")
                 .default_value(if config.is_progress_bar() {
                     "true"
                 } else {
                     "false"
                 })
                 .help("Display a progress bar of the timer's progress")
+                .overrides_with("no-progress-bar")
+                .group("customize"),
+            // Disable the progress bar
+            Arg::new("no-progress-bar")
+                .long("no-progress-bar")
+                .action(ArgAction::SetTrue)
+                .help("Hide the progress bar")
+                .overrides_with("progress-bar")
                 .group("customize"),
             // Set outer padding
             Arg::new("padding")
@@ -163,29 +457,183 @@ fn command(config: &Config) -> Command {
                 .value_parser(clap::value_parser!(u16))
                 .help("Set the outer padding")
                 .group("customize"),
+            // Disable the timer entirely
+            Arg::new("no-timer")
+                .long("no-timer")
+                .action(ArgAction::SetTrue)
+                .help("Don't display a timer, even if time arguments are provided")
+                .group("customize"),
+            // Display the time of day
+            Arg::new("clock")
+                .long("clock")
+                .action(ArgAction::SetTrue)
+                .help("Display the current time, in place of the timer if there is no countdown")
+                .group("customize"),
+            // Repeating work and break phases
+            Arg::new("pomodoro")
+                .long("pomodoro")
+                .action(ArgAction::Append)
+                .num_args(0..)
+                .value_name("work:TIME | break:TIME | cycles:N")
+                .value_parser(move |arg: &str| pomodoro_arg_parser(arg, session_length))
+                .help("Repeat work and break phases, defaults to 'work:25m break:5m cycles:4'")
+                .group("customize"),
+            // Count up instead of down
+            Arg::new("stopwatch")
+                .long("stopwatch")
+                .action(ArgAction::SetTrue)
+                .help("Count the elapsed time up from zero instead of counting down")
+                .group("customize"),
+            // Ask for the settings before starting
+            Arg::new("interactive")
+                .long("interactive")
+                .short('i')
+                .action(ArgAction::SetTrue)
+                .help("Ask for the duration, text and Twitch channel before starting")
+                .group("customize"),
+            // Count down without the TUI
+            Arg::new("background")
+                .long("background")
+                .action(ArgAction::SetTrue)
+                .help("Count down on a single line without the TUI, ringing the bell when finished")
+                .group("customize"),
+            // Use a named duration from the config instead of time arguments
+            Arg::new("timer-preset")
+                .long("timer-preset")
+                .action(ArgAction::Set)
+                .value_name("NAME")
+                .help("Count down the duration of a timer preset from the config")
+                .group("customize"),
+            // Serve the countdown state over HTTP
+            Arg::new("serve")
+                .long("serve")
+                .action(ArgAction::Set)
+                .value_name("PORT")
+                .value_parser(clap::value_parser!(u16).range(1..))
+                .help("Report the countdown as JSON at 'GET /status' on this port")
+                .group("customize"),
             // The positional time arguments "1h 2m 3s"
             Arg::new("time")
                 .help("Time arguments in the format [t]h, [t]m, or [t]s")
                 .action(ArgAction::Append)
                 .num_args(0..)
                 .value_name("TIME")
-                .value_parser(time_arg_parser), // We use a custom parser here
+                .value_parser(move |arg: &str| time_arg_parser(arg, session_length)), // We use a custom parser here
         ])
+        // Only a single kind of duration can be provided, the time arguments are summed up
+        .group(
+            ArgGroup::new("duration")
+                .args(["time", "timer-preset", "pomodoro", "stopwatch"])
+                .multiple(false),
+        )
         .group(ArgGroup::new("info").multiple(true))
         .next_help_heading("Info")
-        .args([Arg::new("dir")
-            .long("dir")
+        .args([
+            Arg::new("dir")
+                .long("dir")
+                .action(ArgAction::SetTrue)
+                .help("Display where the config file should be located")
+                .group("info"),
+            // List the media players which can be selected as the song player
+            Arg::new("list-players")
+                .long("list-players")
+                .action(ArgAction::SetTrue)
+                .help("List the available media players for the song display")
+                .group("info"),
+            // Print render loop statistics on exit
+            Arg::new("perf")
+                .long("perf")
+                .action(ArgAction::SetTrue)
+                .help("Print statistics about the render loop on exit")
+                .group("info"),
+        ]);
+
+    // Use the clipboard contents as text
+    #[cfg(feature = "clipboard")]
+    let command = command.arg(
+        Arg::new("text-from-clipboard")
+            .long("text-from-clipboard")
             .action(ArgAction::SetTrue)
-            .help("Display where the config file should be located")
-            .group("info")])
+            .help("Use the clipboard contents as the text")
+            .help_heading("Customize")
+            .group("customize"),
+    );
+
+    // Render to another terminal device
+    #[cfg(unix)]
+    let command = command.arg(
+        Arg::new("tty")
+            .long("tty")
+            .action(ArgAction::Set)
+            .value_parser(clap::value_parser!(PathBuf))
+            .value_name("PATH")
+            .help("Render to another terminal device like '/dev/pts/3' instead of stdout")
+            .help_heading("Customize")
+            .group("customize"),
+    );
+
+    command
+}
+
+// Return an error if a part of the pomodoro argument was provided more than once
+fn check_pomodoro_conflicts(config: &Config, matches: &ArgMatches) -> Result<(), clap::Error> {
+    let Some(parts) = matches.get_raw("pomodoro") else {
+        return Ok(());
+    };
+
+    let parts: Vec<String> = parts.map(|p| p.to_string_lossy().to_string()).collect();
+
+    for (index, part) in parts.iter().enumerate() {
+        let key = part.split(':').next().unwrap_or_default();
+
+        // Find an earlier part with the same key, like "work:25m" and "work:30m"
+        if let Some(other) = parts[..index]
+            .iter()
+            .find(|p| p.split(':').next() == Some(key))
+        {
+            return Err(command(config).error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "the pomodoro argument '{}' cannot be used with '{}'",
+                    part, other
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Get the current clipboard contents, or None if it is empty or unavailable
+#[cfg(feature = "clipboard")]
+fn clipboard_text() -> Option<String> {
+    let text = arboard::Clipboard::new().ok()?.get_text().ok()?;
+
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(text)
+    }
 }
 
 // Custom parser for time arguments
-fn time_arg_parser(arg: &str) -> Result<TimeValue, String> {
+fn time_arg_parser(arg: &str, session_length: Option<Duration>) -> Result<TimeValue, String> {
+    // An empty argument can't be split into a value and a unit
+    if arg.is_empty() {
+        return Err("Missing time argument".to_string());
+    }
+
+    // A percentage like "25%" is resolved against the configured session length
+    if let Some(percent_str) = arg.strip_suffix('%') {
+        return percent_arg_parser(percent_str, session_length);
+    }
+
     /* Split the string at the last character in the string, the first part is the time value "13"
-     * and the last part is the time unit character "h", "m" or "s"
+     * and the last part is the time unit character "h", "m" or "s". The last character may be
+     * longer than a byte, so the string is split at its start.
      */
-    let (value_str, unit_str) = arg.split_at(arg.len() - 1);
+    let unit_index = arg.char_indices().last().map_or(0, |(index, _)| index);
+    let (value_str, unit_str) = arg.split_at(unit_index);
 
     // Match the time unit character to the corresponding TimeUnit enum variant
     let unit = match unit_str {
@@ -193,7 +641,7 @@ fn time_arg_parser(arg: &str) -> Result<TimeValue, String> {
         "m" => TimeUnit::Minutes,
         "s" => TimeUnit::Seconds,
         // Return a CLI parsing error if the character is not valid
-        _ => return Err("Time arguments must end with 'h', 'm', or 's' suffix".to_string()),
+        _ => return Err("Time arguments must end with 'h', 'm', 's' or '%' suffix".to_string()),
     };
 
     // If no value is provided
@@ -210,17 +658,154 @@ fn time_arg_parser(arg: &str) -> Result<TimeValue, String> {
         )
     })?;
 
+    // Make sure the value in seconds doesn't overflow or exceed the maximum
+    if value
+        .checked_mul(unit.secs())
+        .is_none_or(|secs| secs > MAX_SECS)
+    {
+        return Err(format!(
+            "Time argument exceeds the maximum of {} hours",
+            MAX_HOURS
+        ));
+    }
+
     // If everything went well return a TimeValue instance
     Ok(TimeValue { value, unit })
 }
 
+// Custom parser for the amount of percentage time arguments, like "25" of "25%"
+fn percent_arg_parser(
+    percent_str: &str,
+    session_length: Option<Duration>,
+) -> Result<TimeValue, String> {
+    let session_length = session_length.ok_or(
+        "Percentage time arguments require 'session_length_secs' to be set in the config file",
+    )?;
+
+    let percent = percent_str
+        .parse::<f64>()
+        .ok()
+        .filter(|percent| percent.is_finite() && *percent >= 0.0)
+        .ok_or_else(|| format!("Invalid percentage '{}%'", percent_str))?;
+
+    let secs = session_length.as_secs_f64() * percent / 100.0;
+
+    if secs > MAX_SECS as f64 {
+        return Err(format!(
+            "Time argument exceeds the maximum of {} hours",
+            MAX_HOURS
+        ));
+    }
+
+    Ok(TimeValue {
+        value: secs.round() as u64,
+        unit: TimeUnit::Seconds,
+    })
+}
+
+// Custom parser for pomodoro arguments like "work:25m", "break:5m" or "cycles:4"
+fn pomodoro_arg_parser(
+    arg: &str,
+    session_length: Option<Duration>,
+) -> Result<PomodoroValue, String> {
+    let (key, value) = arg.split_once(':').ok_or(
+        "Pomodoro arguments must be in the format 'work:TIME', 'break:TIME' or 'cycles:N'",
+    )?;
+
+    match key {
+        "work" => Ok(PomodoroValue::Work(
+            time_arg_parser(value, session_length)?.to_duration(),
+        )),
+        "break" => Ok(PomodoroValue::Break(
+            time_arg_parser(value, session_length)?.to_duration(),
+        )),
+        "cycles" => value
+            .parse::<u32>()
+            .ok()
+            .filter(|&cycles| cycles > 0)
+            .map(PomodoroValue::Cycles)
+            .ok_or_else(|| format!("Invalid cycle count '{}', must be at least 1", value)),
+        _ => Err(format!(
+            "Unknown pomodoro argument '{}', must be 'work', 'break' or 'cycles'",
+            key
+        )),
+    }
+}
+
+// Get the duration of a timer preset, exiting with the available presets if it doesn't exist
+fn preset_duration(config: &Config, name: &str) -> Duration {
+    let presets = config.get_presets();
+
+    let Some(value) = presets.get(name) else {
+        let available = if presets.is_empty() {
+            "none, add them to the [presets] table of the config".to_string()
+        } else {
+            presets.keys().cloned().collect::<Vec<_>>().join(", ")
+        };
+
+        command(config)
+            .error(
+                ErrorKind::InvalidValue,
+                format!(
+                    "unknown timer preset '{}', available presets: {}",
+                    name, available
+                ),
+            )
+            .exit();
+    };
+
+    // A preset can consist of multiple time arguments like "1h 30m", which are summed up
+    let session_length = config.get_session_length();
+    let parsed: Result<Vec<TimeValue>, String> = value
+        .split_whitespace()
+        .map(|arg| time_arg_parser(arg, session_length))
+        .collect();
+
+    match parsed {
+        Ok(values) if !values.is_empty() => values.iter().map(TimeValue::to_duration).sum(),
+        Ok(_) => invalid_preset(config, name, "Missing time argument"),
+        Err(e) => invalid_preset(config, name, &e),
+    }
+}
+
+// Exit because the value of a timer preset couldn't be parsed
+fn invalid_preset(config: &Config, name: &str, error: &str) -> ! {
+    command(config)
+        .error(
+            ErrorKind::ValueValidation,
+            format!("invalid timer preset '{}': {}", name, error),
+        )
+        .exit()
+}
+
+// Custom parser for preset arguments
+fn preset_arg_parser(arg: &str) -> Result<Preset, String> {
+    Preset::from_name(arg)
+        .ok_or_else(|| "Invalid preset, must be 'minimal', 'full' or 'overlay'".to_string())
+}
+
 // Custom parser for color arguments
-fn color_arg_parser(arg: &str) -> Result<TomlColor, String> {
+pub(crate) fn color_arg_parser(arg: &str) -> Result<TomlColor, String> {
+    // Parse indices of the 256 color palette like "160"
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_digit()) {
+        return arg.parse::<u8>().map(TomlColor::Indexed).map_err(|_| {
+            format!(
+                "Invalid color index '{}', must be a number between 0 and 255",
+                arg
+            )
+        });
+    }
+
     // Try to map the arg to a color name
     if let Some(named_color) = TomlColor::from_name(arg) {
         return Ok(named_color);
     }
 
+    // Parse hex codes like "#1db954"
+    if arg.starts_with('#') {
+        return TomlColor::from_hex(arg);
+    }
+
     // Check if the argument contains separators
     if !arg.contains(',') {
         return Err("Invalid color name".to_string());
@@ -260,22 +845,331 @@ fn output_dir() -> Result<()> {
     let config_dir = Config::get_config_dir()?
         .into_os_string()
         .into_string()
-        .map_err(|_| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                "Failed converting config directory string",
-            )
-        })?;
+        .map_err(|_| io::Error::other("Failed converting config directory string"))?;
 
     println!("{}", config_dir);
 
     Ok(())
 }
 
+// Ask for the duration, text and Twitch channel, an empty answer keeps the current value.
+// Returns the new duration.
+fn prompt_settings(
+    config: &mut Config,
+    mut duration: Option<Duration>,
+    ask_duration: bool,
+) -> Result<Option<Duration>> {
+    if ask_duration {
+        duration = prompt_duration(duration, config.get_session_length())?;
+    }
+
+    if let Some(text) = prompt("Text", &config.get_text())? {
+        config.set_text(text);
+    }
+
+    if config.is_chat() {
+        let current = config
+            .get_twitch_channel()
+            .unwrap_or_else(|| "no chat".to_string());
+
+        if let Some(channel) = prompt("Twitch channel", &current)? {
+            config.set_twitch_channel(channel);
+        }
+    }
+
+    Ok(duration)
+}
+
+// Ask for the duration until a valid one is entered, an empty answer keeps the current one
+fn prompt_duration(
+    current: Option<Duration>,
+    session_length: Option<Duration>,
+) -> Result<Option<Duration>> {
+    let current_str = current.map_or("no countdown".to_string(), |d| format!("{}s", d.as_secs()));
+
+    loop {
+        let Some(input) = prompt("Duration, like '5m 30s'", &current_str)? else {
+            return Ok(current);
+        };
+
+        match parse_duration_input(&input, session_length) {
+            Ok(duration) => return Ok(Some(duration)),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+// Print the question and read the answer from stdin, returns None if the answer is empty
+fn prompt(question: &str, current: &str) -> Result<Option<String>> {
+    print!("{} [{}]: ", question, current);
+    io::stdout().flush().context("Failed writing prompt")?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .context("Failed reading answer")?;
+
+    let answer = answer.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
+// Parse a duration like "1h 30m" using the same format as the time arguments
+fn parse_duration_input(input: &str, session_length: Option<Duration>) -> Result<Duration, String> {
+    let total = input
+        .split_whitespace()
+        .map(|arg| time_arg_parser(arg, session_length).map(|value| value.to_duration()))
+        .sum::<Result<Duration, String>>()?;
+
+    if total.as_secs() > MAX_SECS {
+        return Err(format!(
+            "The duration exceeds the maximum of {} hours",
+            MAX_HOURS
+        ));
+    }
+
+    Ok(total)
+}
+
+// Output the available media players, one per line
+fn output_players() -> Result<()> {
+    let players = song::list_players()?;
+
+    if players.is_empty() {
+        println!("No media players found");
+    }
+
+    for player in players {
+        println!("{}", player);
+    }
+
+    Ok(())
+}
+
 // Run the App
-fn run_app(mut app: App) -> Result<()> {
+fn run_app(app: &mut App) -> Result<()> {
     let mut terminal = ratatui::init();
     app.run(&mut terminal).context("Failed initializing UI")?;
     ratatui::restore();
     Ok(())
 }
+
+// Run the App on another terminal device, keys are still read from the current terminal
+#[cfg(unix)]
+fn run_app_on_tty(app: &mut App, path: &Path) -> Result<()> {
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .context("Failed opening terminal device")?;
+
+    terminal::enable_raw_mode().context("Failed enabling raw mode")?;
+    execute!(tty, EnterAlternateScreen).context("Failed initializing terminal device")?;
+
+    let mut terminal =
+        Terminal::new(CrosstermBackend::new(tty)).context("Failed initializing terminal device")?;
+    let result = app.run(&mut terminal).context("Failed initializing UI");
+
+    // Restore the same terminal device, even if the App failed
+    let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen, cursor::Show);
+    let _ = terminal::disable_raw_mode();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BigTextSize;
+    use ratatui::style::Color;
+
+    // Resolve the config from a config file and CLI arguments the same way as parse does
+    fn resolve(file: &str, args: &[&str]) -> Config {
+        let config = Config::from_toml(file, None).unwrap();
+        let matches = command(&config)
+            .try_get_matches_from(std::iter::once("brb").chain(args.iter().copied()))
+            .unwrap();
+
+        let preset = matches.get_one::<Preset>("preset").copied();
+        let mut config = Config::from_toml(file, preset).unwrap();
+        apply_matches(&mut config, &matches);
+
+        config
+    }
+
+    #[test]
+    fn file_overrides_defaults() {
+        let config = resolve("padding = 4\nprogress_bar = false", &[]);
+
+        assert_eq!(config.get_padding(), 4);
+        assert!(!config.is_progress_bar());
+        assert!(config.is_hide_timer());
+    }
+
+    #[test]
+    fn preset_overrides_defaults() {
+        let config = resolve("", &["--preset", "minimal"]);
+
+        assert_eq!(config.get_padding(), 0);
+        assert!(!config.is_progress_bar());
+        assert!(!config.is_song_display());
+    }
+
+    #[test]
+    fn file_overrides_preset() {
+        let config = resolve("padding = 3\nchat = false", &["--preset", "full"]);
+
+        assert_eq!(config.get_padding(), 3);
+        assert!(!config.is_chat());
+        assert!(config.is_song_display());
+    }
+
+    #[test]
+    fn cli_overrides_file_and_preset() {
+        let config = resolve(
+            "padding = 3\nchat = false",
+            &[
+                "--preset",
+                "full",
+                "--padding",
+                "7",
+                "--chat",
+                "--no-song-display",
+            ],
+        );
+
+        assert_eq!(config.get_padding(), 7);
+        assert!(config.is_chat());
+        assert!(!config.is_song_display());
+    }
+
+    #[test]
+    fn cli_bool_values() {
+        let config = resolve("", &["--progress-bar", "false", "--hide-timer=false"]);
+
+        assert!(!config.is_progress_bar());
+        assert!(!config.is_hide_timer());
+    }
+
+    #[test]
+    fn reload_keeps_file_values() {
+        let config = Config::from_toml("progress_bar = true", None).unwrap();
+        let matches = command(&config)
+            .try_get_matches_from(["brb", "--padding", "5"])
+            .unwrap();
+
+        // The CLI arguments are applied to the reloaded config again, like on a hot reload
+        let mut reloaded = Config::from_toml("progress_bar = false\nchat = true", None).unwrap();
+        apply_matches(&mut reloaded, &matches);
+
+        assert!(!reloaded.is_progress_bar());
+        assert!(reloaded.is_chat());
+        assert_eq!(reloaded.get_padding(), 5);
+    }
+
+    #[test]
+    fn invalid_values_use_defaults() {
+        let config = resolve(
+            "color = [1, 2]\ntimer_size = \"huge\"\ntext_size = 5\npadding = 2",
+            &[],
+        );
+
+        assert_eq!(config.get_color(), Color::White);
+        assert_eq!(config.get_timer_size(), BigTextSize::Full);
+        assert_eq!(config.get_text_size(), BigTextSize::Quadrant);
+        assert_eq!(config.get_padding(), 2);
+    }
+
+    // Get the kind of error the CLI arguments fail with, or None if they are valid
+    fn conflict(args: &[&str]) -> Option<ErrorKind> {
+        let config = Config::default();
+        let matches = command(&config)
+            .try_get_matches_from(std::iter::once("brb").chain(args.iter().copied()));
+
+        match matches {
+            Ok(matches) => check_pomodoro_conflicts(&config, &matches)
+                .err()
+                .map(|e| e.kind()),
+            Err(e) => Some(e.kind()),
+        }
+    }
+
+    #[test]
+    fn duration_conflicts() {
+        let conflicts = [
+            &["5m", "--stopwatch"][..],
+            &["5m", "--pomodoro"],
+            &["5m", "--timer-preset", "short"],
+            &["--stopwatch", "--pomodoro", "work:25m"],
+        ];
+
+        for args in conflicts {
+            assert_eq!(
+                conflict(args),
+                Some(ErrorKind::ArgumentConflict),
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn pomodoro_part_conflicts() {
+        assert_eq!(
+            conflict(&["--pomodoro", "work:25m", "work:30m"]),
+            Some(ErrorKind::ArgumentConflict)
+        );
+        assert_eq!(conflict(&["--pomodoro", "work:25m", "break:5m"]), None);
+    }
+
+    #[test]
+    fn multiple_time_arguments_allowed() {
+        assert_eq!(conflict(&["1h", "30m", "15s"]), None);
+    }
+
+    // Parse a single time argument to a Duration
+    fn time(arg: &str) -> Result<Duration, String> {
+        time_arg_parser(arg, None).map(|value| value.to_duration())
+    }
+
+    #[test]
+    fn time_argument_maximum() {
+        assert_eq!(time("99h"), Ok(Duration::from_secs(MAX_SECS)));
+        assert_eq!(time("5940m"), Ok(Duration::from_secs(MAX_SECS)));
+        assert_eq!(time("356400s"), Ok(Duration::from_secs(MAX_SECS)));
+
+        assert!(time("100h").is_err());
+        assert!(time("5941m").is_err());
+        assert!(time("356401s").is_err());
+    }
+
+    #[test]
+    fn multibyte_time_unit() {
+        assert!(time("5é").is_err());
+        assert!(time("é").is_err());
+        assert!(time("5分").is_err());
+        assert_eq!(conflict(&["5é"]), Some(ErrorKind::ValueValidation));
+    }
+
+    #[test]
+    fn time_argument_overflow() {
+        assert!(time("9999999999h").is_err());
+        assert!(time(&format!("{}s", u64::MAX)).is_err());
+        assert!(time(&format!("{}m", u64::MAX / 60 + 1)).is_err());
+        assert!(time("99999999999999999999999s").is_err());
+    }
+
+    #[test]
+    fn summed_time_maximum() {
+        assert_eq!(
+            parse_duration_input("98h 59m 60s", None),
+            Ok(Duration::from_secs(MAX_SECS))
+        );
+        assert!(parse_duration_input("99h 1s", None).is_err());
+        assert!(parse_duration_input("99h 99h 99h", None).is_err());
+    }
+
+    #[test]
+    fn invalid_preset_fails() {
+        assert!(Config::from_toml("preset = \"huge\"", None).is_err());
+    }
+}
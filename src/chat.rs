@@ -4,14 +4,22 @@
 */
 
 use anyhow::{Context, Error, Result};
+use chrono::{DateTime, Local};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, BorderType, Borders, List, ListDirection, Padding, Widget},
+    widgets::{
+        Block, BorderType, Borders, List, ListDirection, ListItem, Padding, Sparkline, Widget,
+    },
 };
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::runtime::{Builder, Runtime};
 use tokio::sync::mpsc;
 use twitch_irc::{
@@ -20,15 +28,71 @@ use twitch_irc::{
     ClientConfig, SecureTCPTransport, TwitchIRCClient,
 };
 
+#[cfg(feature = "youtube")]
+use crate::youtube::YouTubeClient;
+use crate::{config::Config, progress};
+
+// A source the chat messages are received from, such as a Twitch channel
+pub trait ChatSource {
+    // Start receiving messages of the provided channel
+    fn start(&mut self, channel: String) -> Result<()>;
+
+    // Leave the current channel and receive the messages of the provided one instead
+    fn change_channel(&mut self, channel: String) -> Result<()>;
+
+    // Check if messages are currently received, which isn't the case while reconnecting
+    fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+// The channel the chat displays, on one of the supported platforms
+pub enum ChatChannel {
+    Twitch(String), // The name of a Twitch channel
+    #[cfg(feature = "youtube")]
+    YouTube(String), // The id of a YouTube live stream
+}
+
+impl ChatChannel {
+    // Get the name of the channel which is displayed above the chat
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Twitch(name) => name,
+            #[cfg(feature = "youtube")]
+            Self::YouTube(video_id) => video_id,
+        }
+    }
+
+    // Get a channel with a different name on the same platform
+    pub fn with_name(&self, name: String) -> Self {
+        match self {
+            Self::Twitch(_) => Self::Twitch(name),
+            #[cfg(feature = "youtube")]
+            Self::YouTube(_) => Self::YouTube(name),
+        }
+    }
+}
+
+// How long the connection to Twitch may be silent until it is considered lost. Twitch answers
+// the PING which is sent every 30 seconds, so a working connection is never silent for longer.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(45);
+
+// The anonymous IRC client used for connecting to Twitch
+type IrcClient = TwitchIRCClient<SecureTCPTransport, StaticLoginCredentials>;
+
 // A twitch client which can connect to a chat
 pub struct TwitchClient {
-    runtime: Runtime,                // The runtime used for receiving twitch messages
-    tx: mpsc::Sender<TwitchMessage>, // The sender used for sending back new messages
+    runtime: Runtime,                   // The runtime used for receiving twitch messages
+    tx: mpsc::Sender<ChatMessage>,      // The sender used for sending back new messages
+    client: Option<IrcClient>,          // The IRC client, available once started
+    last_received: Arc<Mutex<Instant>>, // When the last message of any kind was received
+    reconnecting: Arc<AtomicBool>,      // Whether Twitch just asked to reconnect
 }
 
 impl TwitchClient {
     // Create a new client where the provided Sender is used to send back new messages
-    pub fn new(tx: mpsc::Sender<TwitchMessage>) -> Self {
+    pub fn new(tx: mpsc::Sender<ChatMessage>) -> Self {
         Self {
             runtime: Builder::new_multi_thread()
                 .worker_threads(1)
@@ -36,17 +100,29 @@ impl TwitchClient {
                 .build()
                 .unwrap(),
             tx,
+            client: None,
+            last_received: Arc::new(Mutex::new(Instant::now())),
+            reconnecting: Arc::new(AtomicBool::new(false)),
         }
     }
+}
 
-    // Start this twitch client on the provided channel
-    pub fn start(&mut self, channel: String) -> Result<()> {
+impl ChatSource for TwitchClient {
+    // Start this twitch client on the provided channel, the client reconnects on its own if the
+    // connection is lost
+    fn start(&mut self, channel: String) -> Result<()> {
         let tx = self.tx.clone();
+        let last_received = self.last_received.clone();
+        let reconnecting = self.reconnecting.clone();
+
         // Create a default twitch client config
         let config = ClientConfig::default();
         // Here we log into the twitch API anonymously
-        let (mut incoming_messages, client) =
-            TwitchIRCClient::<SecureTCPTransport, StaticLoginCredentials>::new(config);
+        let (mut incoming_messages, client) = IrcClient::new(config);
+
+        // Keep a handle to the client, so the channel can be changed later on
+        self.client = Some(client.clone());
+        *last_received.lock().unwrap() = Instant::now();
 
         // Spawn a new task on the runtime
         self.runtime.spawn(async move {
@@ -57,6 +133,14 @@ impl TwitchClient {
 
             // While there are messages incoming
             while let Some(message) = incoming_messages.recv().await {
+                // Every message, including the answers to PINGs, means the connection is alive,
+                // until Twitch asks to reconnect
+                *last_received.lock().unwrap() = Instant::now();
+                reconnecting.store(
+                    matches!(message, ServerMessage::Reconnect(_)),
+                    Ordering::Relaxed,
+                );
+
                 // If we receive a "Privmsg", which is just a normal chat message
                 if let ServerMessage::Privmsg(msg) = message {
                     // Turn the senders username color into a Color
@@ -67,9 +151,28 @@ impl TwitchClient {
                     });
                     let color = Color::Rgb(name_color.r, name_color.g, name_color.b);
 
+                    // The most important badge of the sender, moderators and the broadcaster
+                    // are privileged
+                    let badge = [ChatBadge::Broadcaster, ChatBadge::Moderator, ChatBadge::Vip]
+                        .into_iter()
+                        .find(|badge| msg.badges.iter().any(|b| b.name == badge.twitch_name()));
+
+                    // Messages of channel-point rewards with text input carry the reward id
+                    let reward_id = msg.source.tags.0.get("custom-reward-id").cloned().flatten();
+
                     // Create the message and send it into the channel
-                    let chat_message = TwitchMessage::new(color, msg.sender.name, msg.message_text);
-                    tx.send(chat_message).await.unwrap();
+                    let chat_message = ChatMessage::new(
+                        color,
+                        msg.sender.name,
+                        msg.message_text,
+                        badge,
+                        reward_id,
+                    );
+
+                    // Stop receiving once the chat was closed
+                    if tx.send(chat_message).await.is_err() {
+                        break;
+                    }
                 }
             }
 
@@ -79,45 +182,153 @@ impl TwitchClient {
 
         Ok(())
     }
+
+    // Leave the current channel and join the provided one instead
+    fn change_channel(&mut self, channel: String) -> Result<()> {
+        let client = self
+            .client
+            .as_ref()
+            .context("Twitch client has not been started")?;
+
+        client
+            .set_wanted_channels(HashSet::from([channel]))
+            .context("Failed connecting to Twitch channel")
+    }
+
+    fn is_connected(&self) -> bool {
+        !self.reconnecting.load(Ordering::Relaxed)
+            && self.last_received.lock().unwrap().elapsed() < CONNECTION_TIMEOUT
+    }
+}
+
+// How many seconds of chat activity are tracked
+const ACTIVITY_SECONDS: usize = 60;
+
+// The height of the chat activity graph
+const ACTIVITY_GRAPH_HEIGHT: u16 = 2;
+
+// How many messages are kept beyond the displayed ones, so the chat can be scrolled up
+const SCROLLBACK_MESSAGES: usize = 500;
+
+// How many of the newest messages are written to the HTML mirror
+const HTML_MESSAGES: usize = 50;
+
+// The minimum time between two writes of the HTML mirror, so bursts of messages don't thrash it
+const HTML_WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+// The width of the timestamp in front of the messages, including the space after it
+const TIMESTAMP_WIDTH: usize = 6;
+
+// A badge of a chat message sender, which is displayed in front of their name
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChatBadge {
+    Broadcaster, // The owner of the channel
+    Moderator,   // A moderator of the channel
+    Vip,         // A VIP of the channel
+}
+
+impl ChatBadge {
+    // Get the name of the badge in Twitch messages
+    fn twitch_name(&self) -> &'static str {
+        match self {
+            Self::Broadcaster => "broadcaster",
+            Self::Moderator => "moderator",
+            Self::Vip => "vip",
+        }
+    }
+
+    // Get the symbol which is displayed in front of the sender name
+    fn symbol(&self) -> char {
+        match self {
+            Self::Broadcaster => '★',
+            Self::Moderator => '⚔',
+            Self::Vip => '♦',
+        }
+    }
+
+    // Get the style of the symbol, using the colors of the Twitch badges
+    fn style(&self) -> Style {
+        let color = match self {
+            Self::Broadcaster => Color::Red,
+            Self::Moderator => Color::Green,
+            Self::Vip => Color::Magenta,
+        };
+
+        Style::new().fg(color).add_modifier(Modifier::BOLD)
+    }
 }
 
 // A twitch message received by the TwitchClient
 #[derive(Clone, Debug)]
-pub struct TwitchMessage {
-    pub sender_color: Color, // The color of the message senders name
-    pub sender: String,      // The name of the message sender
-    pub message: String,     // The actual message content
+pub struct ChatMessage {
+    pub sender_color: Color,       // The color of the message senders name
+    pub sender: String,            // The name of the message sender
+    pub message: String,           // The actual message content
+    pub privileged: bool,          // Whether the sender is a moderator or the broadcaster
+    pub badge: Option<ChatBadge>,  // The most important badge of the sender, if any
+    pub reward_id: Option<String>, // The id of the redeemed channel-point reward, if any
+    pub received_at: SystemTime,   // When the message was received
+    pub sequence: usize,           // How many messages the chat received before this one
 }
 
-impl TwitchMessage {
-    fn new(sender_color: Color, sender: String, message: String) -> Self {
+impl ChatMessage {
+    pub fn new(
+        sender_color: Color,
+        sender: String,
+        message: String,
+        badge: Option<ChatBadge>,
+        reward_id: Option<String>,
+    ) -> Self {
         Self {
             sender_color,
             sender,
             message,
+            privileged: matches!(badge, Some(ChatBadge::Broadcaster | ChatBadge::Moderator)),
+            badge,
+            reward_id,
+            received_at: SystemTime::now(),
+            sequence: 0,
         }
     }
 
     // I don't really have any details on this monstrosity, but I do know that it successfully
     // wraps lines, so don't touch it
-    fn to_wrapped(&self, max_width: usize) -> Text<'_> {
-        let full_message = format!("{}: {}", self.sender, self.message);
+    fn to_wrapped(&self, max_width: usize, bold_names: bool, badges: bool) -> Text<'_> {
+        // The badge is wrapped together with the message, so its width is taken into account.
+        // A non-breaking space keeps it on the same line as the sender name.
+        let badge = self.badge.filter(|_| badges);
+        let badge_prefix = badge.map_or(String::new(), |badge| format!("{}\u{a0}", badge.symbol()));
+
+        let full_message = format!("{}{}: {}", badge_prefix, self.sender, self.message);
         let wrapped_lines = textwrap::wrap(full_message.as_str(), max_width);
 
         let mut lines = Vec::new();
+        let mut badge_chars_left = badge_prefix.len();
         let mut sender_chars_left = self.sender.len();
 
+        // Style of the sender name, optionally emphasized
+        let mut sender_style = Style::default().fg(self.sender_color);
+        if bold_names {
+            sender_style = sender_style.add_modifier(Modifier::BOLD);
+        }
+
         for wrapped_line in wrapped_lines.iter() {
             let mut spans = Vec::new();
             let mut current_idx = 0;
 
-            if sender_chars_left > 0 {
-                let chars_to_color = sender_chars_left.min(wrapped_line.len());
-                let sender_part = &wrapped_line[0..chars_to_color];
-                spans.push(Span::styled(
-                    sender_part.to_string(),
-                    Style::default().fg(self.sender_color),
-                ));
+            if let (Some(badge), true) = (badge, badge_chars_left > 0) {
+                let chars_to_color = badge_chars_left.min(wrapped_line.len());
+                let badge_part = &wrapped_line[0..chars_to_color];
+                spans.push(Span::styled(badge_part.to_string(), badge.style()));
+
+                badge_chars_left -= chars_to_color;
+                current_idx += chars_to_color;
+            }
+
+            if sender_chars_left > 0 && current_idx < wrapped_line.len() {
+                let chars_to_color = sender_chars_left.min(wrapped_line.len() - current_idx);
+                let sender_part = &wrapped_line[current_idx..current_idx + chars_to_color];
+                spans.push(Span::styled(sender_part.to_string(), sender_style));
 
                 sender_chars_left -= chars_to_color;
                 current_idx += chars_to_color;
@@ -138,54 +349,425 @@ impl TwitchMessage {
 
         Text::from(lines)
     }
+
+    // Wrap only the message content, indented below the name of a previous message
+    fn to_wrapped_continued(&self, max_width: usize) -> Text<'_> {
+        let options = textwrap::Options::new(max_width)
+            .initial_indent("  ")
+            .subsequent_indent("  ");
+
+        textwrap::wrap(&self.message, options)
+            .into_iter()
+            .map(|line| Line::from(line.into_owned()))
+            .collect()
+    }
+
+    // Prefix the first line of the wrapped message with the time it was received, like "14:05",
+    // the following lines are indented to line up with the message
+    fn with_timestamp<'a>(&self, text: Text<'a>) -> Text<'a> {
+        let timestamp = DateTime::<Local>::from(self.received_at)
+            .format("%H:%M ")
+            .to_string();
+
+        text.lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut line)| {
+                let prefix = if i == 0 {
+                    Span::styled(timestamp.clone(), Style::new().add_modifier(Modifier::DIM))
+                } else {
+                    Span::raw(" ".repeat(TIMESTAMP_WIDTH))
+                };
+
+                line.spans.insert(0, prefix);
+                line
+            })
+            .collect()
+    }
 }
 
-// This is the actual TwitchChat widget which is displayed in the UI
-pub struct TwitchChat {
-    accent_color: Color,                      // Accent color which should be used
-    max_height: Arc<Mutex<usize>>,            // Historical maximum chat area height
-    channel_name: String,                     // Name of the chats twitch channel
-    twitch_client: TwitchClient,              // TwitchClient used for receiving messages
-    messages: Arc<Mutex<Vec<TwitchMessage>>>, // All currently stored messages
-    rx: mpsc::Receiver<TwitchMessage>,        // Receiver for getting messages from TwitchClient
+// This is the actual ChatDisplay widget which is displayed in the UI
+pub struct ChatDisplay {
+    accent_color: Color,                    // Accent color which should be used
+    bold_names: bool,                       // Whether sender names are rendered bold
+    message_spacing: u16,                   // Blank lines between messages
+    group_senders: bool,                    // Whether consecutive messages share one name
+    timestamps: bool,                       // Whether messages are prefixed with the time
+    badges: bool,                           // Whether sender badges are shown before names
+    zebra_color: Option<Color>,             // Background of every other message, if shaded
+    label: String,                          // The label at the bottom, omitted if empty
+    title: bool,                            // Whether the channel name is shown at the top
+    border: bool,                           // Whether the border around the chat is drawn
+    activity_graph: bool,                   // Whether the chat activity graph is rendered
+    activity: VecDeque<u64>,                // Messages received per second, oldest first
+    activity_second: Instant,               // The start of the current activity second
+    max_height: Arc<Mutex<usize>>,          // Historical maximum chat area height
+    channel: ChatChannel,                   // The channel the messages are received from
+    source: Box<dyn ChatSource>,            // The source used for receiving messages
+    messages: Arc<Mutex<Vec<ChatMessage>>>, // All currently stored messages
+    pending: VecDeque<ChatMessage>,         // Received messages which aren't displayed yet
+    reveal_rate: Option<usize>,             // Maximum amount of messages revealed per poll
+    message_count: usize,                   // How many messages were received in total
+    scroll_offset: usize,                   // How many messages the chat is scrolled up
+    unseen: usize,                          // Messages revealed below while scrolled up
+    scroll_symbol: String,                  // The symbol in front of the scroll indicator
+    scroll_color: Color,                    // The color of the scroll indicator
+    html_path: Option<PathBuf>,             // The file the chat is mirrored to as HTML
+    html_outdated: bool,                    // Whether messages were revealed since the last write
+    html_written: Option<Instant>,          // When the HTML mirror was last written
+    ignore_users: Vec<String>, // Lowercase names of the senders whose messages are dropped
+    ignore_words: Vec<String>, // Lowercase words which drop the messages containing them, see word_list
+    rx: mpsc::Receiver<ChatMessage>, // Receiver for getting messages from the source
 }
 
-impl TwitchChat {
-    pub fn new(accent_color: Color, channel_name: String) -> Self {
+impl ChatDisplay {
+    // Create a chat of the provided channel, its settings are set using apply_config
+    pub fn new(channel: ChatChannel) -> Self {
         let (tx, rx) = mpsc::channel(100);
+
+        // Receive the messages from the platform of the channel
+        let source: Box<dyn ChatSource> = match channel {
+            ChatChannel::Twitch(_) => Box::new(TwitchClient::new(tx)),
+            #[cfg(feature = "youtube")]
+            ChatChannel::YouTube(_) => Box::new(YouTubeClient::new(tx)),
+        };
+
         Self {
-            accent_color,
+            accent_color: Color::White,
+            bold_names: false,
+            message_spacing: 0,
+            group_senders: false,
+            timestamps: false,
+            badges: false,
+            zebra_color: None,
+            label: String::new(),
+            title: true,
+            border: true,
+            activity_graph: false,
+            activity: VecDeque::from([0]),
+            activity_second: Instant::now(),
             max_height: Arc::new(Mutex::new(0)),
-            channel_name,
-            twitch_client: TwitchClient::new(tx),
+            channel,
+            source,
             messages: Arc::new(Mutex::new(Vec::new())),
+            pending: VecDeque::new(),
+            reveal_rate: None,
+            message_count: 0,
+            scroll_offset: 0,
+            unseen: 0,
+            scroll_symbol: String::new(),
+            scroll_color: Color::White,
+            html_path: None,
+            html_outdated: false,
+            html_written: None,
+            ignore_users: Vec::new(),
+            ignore_words: Vec::new(),
             rx,
         }
     }
 
-    // Start receiving messages for this TwitchChat
+    // Apply the chat settings of the config, which is done again whenever the config is reloaded
+    pub fn apply_config(&mut self, config: &Config) {
+        self.accent_color = config.get_color();
+        self.bold_names = config.is_chat_bold_names();
+        self.message_spacing = config.get_chat_message_spacing();
+        self.label = config.get_chat_label();
+        self.title = config.is_chat_title();
+        self.border = config.is_chat_border();
+        self.reveal_rate = config.get_chat_reveal_rate();
+        self.activity_graph = config.is_chat_activity_graph();
+
+        let scroll_color = config.get_chat_scroll_color().unwrap_or(config.get_color());
+        self.set_scroll_indicator(config.get_chat_scroll_symbol(), scroll_color);
+        self.set_group_senders(config.is_chat_group_senders());
+        self.set_timestamps(config.is_chat_timestamps());
+        self.set_badges(config.is_chat_badges());
+        self.set_html_mirror(config.get_chat_html());
+        self.set_ignored(
+            config.get_chat_ignore_users(),
+            config.get_chat_ignore_words(),
+        );
+
+        // Without a configured color, the shading is a very dark version of the accent color
+        let zebra_color = config.is_chat_zebra().then(|| {
+            config.get_chat_zebra_color().unwrap_or_else(|| {
+                let shade = progress::scale_brightness(config.get_color(), 0.15);
+                progress::reduce_color(shade, config.get_color_depth())
+            })
+        });
+        self.set_zebra_color(zebra_color);
+    }
+
+    // Set whether consecutive messages of the same sender are grouped under one name
+    pub fn set_group_senders(&mut self, group_senders: bool) {
+        self.group_senders = group_senders;
+    }
+
+    // Set whether the messages are prefixed with the time they were received
+    pub fn set_timestamps(&mut self, timestamps: bool) {
+        self.timestamps = timestamps;
+    }
+
+    // Set whether the badges of moderators, VIPs and the broadcaster are shown before their names
+    pub fn set_badges(&mut self, badges: bool) {
+        self.badges = badges;
+    }
+
+    // Set the background color every other message is shaded with, None disables the shading
+    pub fn set_zebra_color(&mut self, zebra_color: Option<Color>) {
+        self.zebra_color = zebra_color;
+    }
+
+    // Set the symbol and color of the indicator which is shown while the chat is scrolled up
+    pub fn set_scroll_indicator(&mut self, symbol: String, color: Color) {
+        self.scroll_symbol = symbol;
+        self.scroll_color = color;
+    }
+
+    // Set the file the recent messages are mirrored to as HTML, None disables the mirror
+    pub fn set_html_mirror(&mut self, path: Option<PathBuf>) {
+        self.html_path = path;
+    }
+
+    // Set the senders and words whose messages are dropped, both are matched regardless of case
+    pub fn set_ignored(&mut self, users: Vec<String>, words: Vec<String>) {
+        self.ignore_users = users
+            .into_iter()
+            .map(|user| user.trim().to_lowercase())
+            .filter(|user| !user.is_empty())
+            .collect();
+        self.ignore_words = words
+            .iter()
+            .map(|word| word_list(word))
+            .filter(|word| !word.trim().is_empty())
+            .collect();
+    }
+
+    // Check whether a message is dropped, because of its sender or a word it contains
+    fn is_ignored(&self, message: &ChatMessage) -> bool {
+        if self.ignore_users.is_empty() && self.ignore_words.is_empty() {
+            return false;
+        }
+
+        let sender = message.sender.to_lowercase();
+        if self.ignore_users.contains(&sender) {
+            return true;
+        }
+
+        // Only whole words match, so ignoring "ass" doesn't drop a message containing "class"
+        let content = word_list(&message.message);
+        self.ignore_words.iter().any(|word| content.contains(word))
+    }
+
+    // Start receiving messages for this ChatDisplay
     pub fn start(&mut self) -> Result<()> {
-        self.twitch_client
-            .start(self.channel_name.clone())
-            .context("Failed starting Twitch client")
+        self.source
+            .start(self.channel.name().to_string())
+            .context("Failed starting chat client")
     }
 
-    // Poll for new messages
-    pub fn poll_messages(&mut self) {
-        while let Ok(message) = self.rx.try_recv() {
-            let mut messages = self.messages.lock().unwrap();
+    // Switch to a different channel, clearing all messages of the previous one
+    pub fn change_channel(&mut self, channel_name: String) -> Result<()> {
+        self.source
+            .change_channel(channel_name.clone())
+            .context("Failed changing chat channel")?;
 
-            messages.push(message);
+        self.channel = self.channel.with_name(channel_name);
+
+        // Discard messages of the previous channel which haven't been polled or displayed yet
+        while self.rx.try_recv().is_ok() {}
+        self.pending.clear();
+
+        self.messages.lock().unwrap().clear();
+        self.scroll_to_bottom();
+
+        Ok(())
+    }
+
+    // Get the displayed channel
+    pub fn get_channel(&self) -> &ChatChannel {
+        &self.channel
+    }
+
+    // Poll for new messages, returning the newly received ones
+    pub fn poll_messages(&mut self) -> Vec<ChatMessage> {
+        let mut received = Vec::new();
+
+        while let Ok(mut message) = self.rx.try_recv() {
+            if self.is_ignored(&message) {
+                continue;
+            }
+
+            message.sequence = self.message_count;
+            self.pending.push_back(message.clone());
+            self.message_count += 1;
+            received.push(message);
         }
+
+        // Reveal the pending messages, at most as many as the reveal rate allows
+        let reveal_count = self
+            .reveal_rate
+            .map_or(self.pending.len(), |rate| rate.min(self.pending.len()));
+
+        self.messages
+            .lock()
+            .unwrap()
+            .extend(self.pending.drain(..reveal_count));
+
+        if reveal_count > 0 {
+            self.html_outdated = true;
+        }
+
+        self.write_html_mirror();
+
+        // Keep the view in place while scrolled up, and count the messages which aren't seen
+        if self.scroll_offset > 0 {
+            self.scroll_offset += reveal_count;
+            self.unseen += reveal_count;
+        }
+
+        self.track_activity(received.len() as u64);
+
+        received
     }
+
+    // Write the newest messages to the HTML mirror, at most once per write interval
+    fn write_html_mirror(&mut self) {
+        let Some(path) = self.html_path.as_ref() else {
+            return;
+        };
+
+        if !self.html_outdated
+            || self
+                .html_written
+                .is_some_and(|written| written.elapsed() < HTML_WRITE_INTERVAL)
+        {
+            return;
+        }
+
+        let html = {
+            let messages = self.messages.lock().unwrap();
+            chat_html(&messages[messages.len().saturating_sub(HTML_MESSAGES)..])
+        };
+
+        // Write to a temporary file first, so the overlay never loads a half written file.
+        // Errors are ignored as they would mess with the TUI, the next write tries again.
+        let temp_path = path.with_extension("tmp");
+        if fs::write(&temp_path, html).is_ok() {
+            let _ = fs::rename(&temp_path, path);
+        }
+
+        self.html_outdated = false;
+        self.html_written = Some(Instant::now());
+    }
+
+    // Scroll up by one message, at most until the oldest stored message
+    pub fn scroll_up(&mut self) {
+        let max_offset = self.messages.lock().unwrap().len().saturating_sub(1);
+        self.scroll_offset = (self.scroll_offset + 1).min(max_offset);
+    }
+
+    // Scroll down by one message
+    pub fn scroll_down(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+
+        if self.scroll_offset == 0 {
+            self.unseen = 0;
+        }
+    }
+
+    // Jump back to the newest messages
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = 0;
+        self.unseen = 0;
+    }
+
+    // Add the received message count to the activity of the current second
+    fn track_activity(&mut self, count: u64) {
+        // Start a new bucket for every second which has passed
+        let elapsed_secs = self.activity_second.elapsed().as_secs();
+        for _ in 0..elapsed_secs.min(ACTIVITY_SECONDS as u64) {
+            self.activity.push_back(0);
+        }
+        self.activity_second += Duration::from_secs(elapsed_secs);
+
+        // Only keep the activity of the tracked seconds
+        while self.activity.len() > ACTIVITY_SECONDS {
+            self.activity.pop_front();
+        }
+
+        if let Some(current) = self.activity.back_mut() {
+            *current += count;
+        }
+    }
+
+    // Get how many messages were received in total
+    #[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+    pub fn get_message_count(&self) -> usize {
+        self.message_count
+    }
+}
+
+// Build a self-contained HTML page of the messages, which reloads itself to show new ones
+fn chat_html(messages: &[ChatMessage]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta http-equiv=\"refresh\" content=\"1\">\n\
+         <style>body { background: transparent; color: #fff; font-family: sans-serif; } \
+         p { margin: 0.2em 0; } b { margin-right: 0.4em; }</style>\n\
+         </head>\n<body>\n",
+    );
+
+    for message in messages {
+        let (r, g, b) = progress::to_rgb(message.sender_color);
+
+        html.push_str(&format!(
+            "<p><b style=\"color: #{:02x}{:02x}{:02x}\">{}</b>{}</p>\n",
+            r,
+            g,
+            b,
+            escape_html(&message.sender),
+            escape_html(&message.message)
+        ));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
 }
 
-// Implement Widget for the TwitchChat so it can be rendered
-impl Widget for &TwitchChat {
+// Escape the characters which have a special meaning in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Get the lowercase words of a text, separated and surrounded by single spaces, so whole words
+// and phrases can be matched with contains, regardless of the punctuation between them
+fn word_list(text: &str) -> String {
+    let words = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(" {} ", words)
+}
+
+// Implement Widget for the ChatDisplay so it can be rendered
+impl Widget for &ChatDisplay {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        // The channel name text at the top
+        // The channel name at the top, which shows when the connection is lost and no messages arrive
+        let title = if self.source.is_connected() {
+            format!(" {} ", self.channel.name())
+        } else {
+            format!(" {} (reconnecting…) ", self.channel.name())
+        };
+
         let title_name = Line::from(Span::styled(
-            format!(" {} ", self.channel_name),
+            title,
             Style::default()
                 .fg(self.accent_color)
                 .add_modifier(Modifier::BOLD)
@@ -193,28 +775,55 @@ impl Widget for &TwitchChat {
         ))
         .alignment(Alignment::Center);
 
-        // The "chat" text at the bottom
-        let title_text = Line::from(Span::styled(
-            " chat ",
-            Style::new()
-                .add_modifier(Modifier::BOLD)
-                .add_modifier(Modifier::ITALIC),
-        ))
-        .alignment(Alignment::Center);
+        let mut chat_display = Block::default().padding(Padding::horizontal(1));
+
+        // Border of the chat, unless it is disabled
+        if self.border {
+            chat_display = chat_display
+                .border_type(BorderType::Rounded)
+                .borders(Borders::ALL);
+        }
 
-        // Border of the chat
-        let chat_display = Block::default()
-            .title_top(title_name)
-            .title_bottom(title_text)
-            .border_type(BorderType::Rounded)
-            .borders(Borders::ALL)
-            .padding(Padding::horizontal(1));
+        // The title at the top, unless it is hidden
+        if self.title {
+            chat_display = chat_display.title_top(title_name);
+        }
+
+        // The label text at the bottom, unless it is empty
+        if !self.label.is_empty() {
+            let title_text = Line::from(Span::styled(
+                format!(" {} ", self.label),
+                Style::new()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::ITALIC),
+            ))
+            .alignment(Alignment::Center);
+
+            chat_display = chat_display.title_bottom(title_text);
+        }
 
         // Lock message vector
         let mut messages = self.messages.lock().unwrap();
 
         // Get a messages area inside the chat border
-        let messages_area = chat_display.inner(area);
+        let mut messages_area = chat_display.inner(area);
+
+        // If the activity graph is enabled, reserve some space for it below the messages
+        if self.activity_graph && messages_area.height > ACTIVITY_GRAPH_HEIGHT {
+            messages_area.height -= ACTIVITY_GRAPH_HEIGHT;
+
+            let graph_area = Rect {
+                y: messages_area.bottom(),
+                height: ACTIVITY_GRAPH_HEIGHT,
+                ..messages_area
+            };
+
+            let activity: Vec<u64> = self.activity.iter().copied().collect();
+            Sparkline::default()
+                .data(&activity)
+                .style(Style::new().fg(self.accent_color))
+                .render(graph_area, buf);
+        }
 
         // Lock the max height
         let mut max_height_lock = self.max_height.lock().unwrap();
@@ -225,8 +834,8 @@ impl Widget for &TwitchChat {
             *max_height_lock = messages_area.height as usize;
         }
 
-        // The stored messages limit is the same as the max height
-        let display_message_limit = *max_height_lock;
+        // The stored messages limit is the max height, plus the messages kept for scrolling up
+        let display_message_limit = *max_height_lock + SCROLLBACK_MESSAGES;
 
         // If there are any excess messages
         if messages.len() > display_message_limit {
@@ -235,18 +844,110 @@ impl Widget for &TwitchChat {
             messages.drain(0..excess);
         }
 
-        // Build the Text widgets out of the chat messages
-        let texts: Vec<Text> = messages
-            .iter()
-            .rev()
-            .map(|message| message.to_wrapped(messages_area.width as usize))
-            .collect();
+        // Older messages might have been removed, so the offset can't go beyond the oldest one
+        let scroll_offset = self.scroll_offset.min(messages.len().saturating_sub(1));
+
+        // While scrolled up, show an indicator below the messages so it's clear they aren't live
+        if scroll_offset > 0 && messages_area.height > 1 {
+            messages_area.height -= 1;
+
+            let indicator_area = Rect {
+                y: messages_area.bottom(),
+                height: 1,
+                ..messages_area
+            };
+
+            let mut indicator = format!("{} scrolled up", self.scroll_symbol);
+            if self.unseen > 0 {
+                indicator.push_str(&format!(" — {} new below", self.unseen));
+            }
+
+            Line::from(Span::styled(
+                indicator.trim_start().to_string(),
+                Style::new()
+                    .fg(self.scroll_color)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .alignment(Alignment::Center)
+            .render(indicator_area, buf);
+        }
+
+        // Build the list items out of the chat messages
+        let mut items: Vec<ListItem> = Vec::new();
+
+        // The displayed messages, newest first
+        let visible: Vec<&ChatMessage> = messages.iter().rev().skip(scroll_offset).collect();
+
+        // Whether a message continues the group of the older message from the same sender
+        let continues_group = |index: usize| {
+            self.group_senders
+                && visible
+                    .get(index + 1)
+                    .is_some_and(|older| older.sender == visible[index].sender)
+        };
+
+        for (i, message) in visible.iter().enumerate() {
+            // Separate messages with blank lines if spacing is configured, except within a group
+            if i > 0 && self.message_spacing > 0 && !continues_group(i - 1) {
+                items.push(ListItem::new(Text::from(vec![
+                    Line::default();
+                    self.message_spacing as usize
+                ])));
+            }
+
+            // The timestamp takes up some of the width the message is wrapped to
+            let wrap_width = if self.timestamps {
+                (messages_area.width as usize).saturating_sub(TIMESTAMP_WIDTH)
+            } else {
+                messages_area.width as usize
+            };
+
+            let mut text = if continues_group(i) {
+                message.to_wrapped_continued(wrap_width)
+            } else {
+                message.to_wrapped(wrap_width, self.bold_names, self.badges)
+            };
+
+            if self.timestamps {
+                text = message.with_timestamp(text);
+            }
+
+            // Shade every other message across its full width, including all wrapped lines. The
+            // shading is based on the order the messages were received in, so it stays the same
+            // for every message when new ones arrive or the chat is scrolled.
+            let mut item = ListItem::new(text);
+            if let (Some(zebra_color), 1) = (self.zebra_color, message.sequence % 2) {
+                item = item.style(Style::new().bg(zebra_color));
+            }
+
+            items.push(item);
+        }
 
         // Create a new List for the chat messages and make it go bottom to top
-        let list = List::new(texts).direction(ListDirection::BottomToTop);
+        let list = List::new(items).direction(ListDirection::BottomToTop);
 
         // And finally render the chat
         chat_display.render(area, buf);
         list.render(messages_area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Check whether an ignored word drops a message with the provided content
+    fn drops(word: &str, content: &str) -> bool {
+        word_list(content).contains(&word_list(word))
+    }
+
+    #[test]
+    fn ignored_words_match_whole_words() {
+        assert!(drops("ass", "what an ass!"));
+        assert!(drops("Ass", "ASS"));
+        assert!(!drops("ass", "first class"));
+        assert!(!drops("ass", "you shall not pass"));
+        assert!(drops("bad phrase", "such a BAD, phrase"));
+        assert!(!drops("bad phrase", "bad phrases"));
+    }
+}
@@ -1,17 +1,62 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::style::Color;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use serde_with::{serde_as, DefaultOnError};
-use std::{fs, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tui_big_text::PixelSize;
+
+use crate::progress;
+
+// Environment variable which overrides the config directory
+const CONFIG_DIR_ENV: &str = "BRB_CONFIG_DIR";
+
+// The name of the config file inside the config directory
+const CONFIG_FILE: &str = "brb.toml";
+
+// The files which are stored next to the config file
+const TIMER_STATE_FILE: &str = "timer_state.toml";
+const LAST_CHANNEL_FILE: &str = "last_channel";
 
 #[derive(Debug, Deserialize, Clone)]
 #[serde(untagged)]
 // A color which is deserialized from the toml config file
 pub enum TomlColor {
     Rgb { r: u8, g: u8, b: u8 }, // An RGB color value
+    Indexed(u8),                 // A color of the 256 color palette
     Name(String), // The name of a color preset, such as "red", "yellow", "white", ...
 }
 
+// Named colors beyond the 8 basic ANSI colors, with their RGB values
+const EXTENDED_COLORS: [(&str, (u8, u8, u8)); 20] = [
+    ("orange", (255, 165, 0)),
+    ("purple", (128, 0, 128)),
+    ("pink", (255, 192, 203)),
+    ("brown", (165, 42, 42)),
+    ("gray", (128, 128, 128)),
+    ("grey", (128, 128, 128)),
+    ("silver", (192, 192, 192)),
+    ("lime", (0, 255, 0)),
+    ("olive", (128, 128, 0)),
+    ("teal", (0, 128, 128)),
+    ("turquoise", (64, 224, 208)),
+    ("navy", (0, 0, 128)),
+    ("indigo", (75, 0, 130)),
+    ("violet", (238, 130, 238)),
+    ("lavender", (230, 230, 250)),
+    ("maroon", (128, 0, 0)),
+    ("crimson", (220, 20, 60)),
+    ("coral", (255, 127, 80)),
+    ("salmon", (250, 128, 114)),
+    ("gold", (255, 215, 0)),
+];
+
 impl TomlColor {
     // Get a TomlColor from a color name, or None if this name was not found
     pub fn from_name(name: &str) -> Option<Self> {
@@ -26,24 +71,539 @@ impl TomlColor {
             "magenta" => Some(Self::Name(name)),
             "cyan" => Some(Self::Name(name)),
             "white" => Some(Self::Name(name)),
+            _ if extended_color(&name).is_some() => Some(Self::Name(name)),
+            _ => name.parse::<u8>().ok().map(Self::Indexed),
+        }
+    }
+
+    // Get a TomlColor from a hex code like "#1db954", where "#f0c" is short for "#ff00cc"
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let digits = hex
+            .strip_prefix('#')
+            .ok_or_else(|| "Hex colors must start with '#'".to_string())?;
+
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "Invalid hex color '{}', must only contain 0-9 and a-f",
+                hex
+            ));
+        }
+
+        // Expand the shorthand by repeating every digit
+        let digits = match digits.len() {
+            3 => digits.chars().flat_map(|c| [c, c]).collect(),
+            6 => digits.to_string(),
+            length => {
+                return Err(format!(
+                    "Invalid hex color length, must be 3 or 6 digits, {} were provided",
+                    length
+                ))
+            }
+        };
+
+        let channel = |index: usize| u8::from_str_radix(&digits[index..index + 2], 16).unwrap();
+
+        Ok(Self::Rgb {
+            r: channel(0),
+            g: channel(2),
+            b: channel(4),
+        })
+    }
+}
+
+// Get the RGB values of an extended color name, or None if this name was not found
+fn extended_color(name: &str) -> Option<(u8, u8, u8)> {
+    EXTENDED_COLORS
+        .iter()
+        .find(|(color_name, _)| *color_name == name)
+        .map(|(_, rgb)| *rgb)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+// A curated combination of config values, which the config file values override
+pub enum Preset {
+    Minimal, // Just the timer and text with little padding
+    Full,    // Every element, including the chat activity and a progress ring
+    Overlay, // A compact timer bar for stream overlays
+}
+
+impl Preset {
+    // Get a Preset from its name, or None if this name was not found
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "minimal" => Some(Self::Minimal),
+            "full" => Some(Self::Full),
+            "overlay" => Some(Self::Overlay),
+            _ => None,
+        }
+    }
+
+    // Apply the values of this preset to the config, except the ones the config file sets
+    fn apply(&self, config: &mut Config, file_values: &toml::Table) {
+        match self {
+            Self::Minimal => minimal_preset(config, file_values),
+            Self::Full => full_preset(config, file_values),
+            Self::Overlay => overlay_preset(config, file_values),
+        }
+    }
+}
+
+// Set the config fields to the values of a preset, unless the config file sets them itself
+macro_rules! preset_values {
+    ($config:ident, $file_values:ident, { $($field:ident: $value:expr),* $(,)? }) => {
+        $(
+            if !$file_values.contains_key(stringify!($field)) {
+                $config.$field = $value;
+            }
+        )*
+    };
+}
+
+// This function will apply the values of the minimal preset
+fn minimal_preset(config: &mut Config, file_values: &toml::Table) {
+    preset_values!(config, file_values, {
+        padding: 0,
+        progress_bar: false,
+        timer_style: TimerStyle::Big,
+        timer_font: TimerFont::Block,
+        digit_animation: false,
+        breathe: false,
+        song_display: false,
+        chat_label: String::new(),
+        chat_title: false,
+        chat_border: false,
+    });
+}
+
+// This function will apply the values of the full preset
+fn full_preset(config: &mut Config, file_values: &toml::Table) {
+    preset_values!(config, file_values, {
+        chat: true,
+        chat_activity_graph: true,
+        song_display: true,
+        show_channel: true,
+        progress_bar: true,
+        progress_style: ProgressStyle::Ring,
+        digit_animation: true,
+    });
+}
+
+// This function will apply the values of the overlay preset
+fn overlay_preset(config: &mut Config, file_values: &toml::Table) {
+    preset_values!(config, file_values, {
+        padding: 0,
+        chat: false,
+        song_display: false,
+        show_channel: false,
+        timer_style: TimerStyle::Bar,
+        content_valign: VerticalAlign::Top,
+    });
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// How many colors the terminal supports
+pub enum ColorDepth {
+    Truecolor, // Any RGB color
+    #[serde(rename = "256")]
+    Ansi256, // The 256 color palette
+    #[serde(rename = "16")]
+    Ansi16, // The 16 basic ANSI colors
+}
+
+impl ColorDepth {
+    // Detect the supported colors using the environment variables set by the terminal
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return Self::Truecolor;
+        }
+
+        match env::var("TERM") {
+            Ok(term) if term.contains("256color") => Self::Ansi256,
+            Ok(term) if !term.is_empty() => Self::Ansi16,
+            // Windows terminals don't set TERM, but support truecolor
+            _ => Self::Truecolor,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// The position of the song display in the layout
+pub enum SongPosition {
+    Top,    // Above the timer
+    Bottom, // Below everything else
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// How the progress of the countdown is displayed
+pub enum ProgressStyle {
+    Bar,  // A horizontal bar below the text
+    Ring, // A thin ring around the timer
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// How the timer is displayed
+pub enum TimerStyle {
+    Big, // Big digits with a separate progress bar
+    Bar, // A compact bar with the time on top of it
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+// The font of the big timer digits
+pub enum TimerFont {
+    Block,        // The big block font
+    SevenSegment, // Segments like on a classic LCD clock
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+// The pixel size of big text, smaller sizes fit the text into less space
+pub enum BigTextSize {
+    Full,        // Every pixel is a full cell
+    HalfHeight,  // Two pixels stacked in a cell
+    HalfWidth,   // Two pixels side by side in a cell
+    Quadrant,    // Four pixels in a cell
+    ThirdHeight, // Three pixels stacked in a cell
+    Sextant,     // Six pixels in a cell
+}
+
+impl BigTextSize {
+    // Get the matching PixelSize of the BigText widget
+    pub fn pixel_size(self) -> PixelSize {
+        match self {
+            Self::Full => PixelSize::Full,
+            Self::HalfHeight => PixelSize::HalfHeight,
+            Self::HalfWidth => PixelSize::HalfWidth,
+            Self::Quadrant => PixelSize::Quadrant,
+            Self::ThirdHeight => PixelSize::ThirdHeight,
+            Self::Sextant => PixelSize::Sextant,
+        }
+    }
+
+    // Get the width and height of a single character in cells
+    pub fn char_size(self) -> (u16, u16) {
+        match self {
+            Self::Full => (8, 8),
+            Self::HalfHeight => (8, 4),
+            Self::HalfWidth => (4, 8),
+            Self::Quadrant => (4, 4),
+            Self::ThirdHeight => (8, 3),
+            Self::Sextant => (4, 3),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// The vertical alignment of the content
+pub enum VerticalAlign {
+    Top,    // In the top part of the screen
+    Center, // In the center of the screen
+    Bottom, // In the bottom part of the screen
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// How the remaining time is rounded to whole seconds for display
+pub enum TimerRound {
+    Floor, // Round down, the timer shows "00:00" during the last second
+    Ceil,  // Round up, the timer shows "00:05" for the entire first second
+    Round, // Round to the nearest second
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// What happens if stdout isn't a terminal, like when it is redirected to a file
+pub enum NonTtyMode {
+    Error,    // Exit with an error
+    Headless, // Print the remaining time on a new line whenever it changes
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// The clock which measures the elapsed countdown time
+pub enum ClockSource {
+    Monotonic, // Never jumps, but might stand still while the system sleeps
+    Wallclock, // Keeps counting while the system sleeps, but follows system clock changes
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+// When the exit commands and script run
+pub enum ExitCommandsOn {
+    Always, // When exiting brb
+    Finish, // When the countdown finishes, but not when exiting brb
+    Quit,   // When exiting brb before the countdown has finished
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+// An element of the vertical layout
+pub enum LayoutElement {
+    Song,     // The current song
+    Timer,    // The countdown
+    Text,     // The text
+    Progress, // The progress bar
+    Channel,  // The Twitch channel callout
+}
+
+impl LayoutElement {
+    // Get a LayoutElement from its name, or None if this name was not found
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "song" => Some(Self::Song),
+            "timer" => Some(Self::Timer),
+            "text" => Some(Self::Text),
+            "progress" => Some(Self::Progress),
+            "channel" => Some(Self::Channel),
             _ => None,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+// A key with optional modifiers, such as "q", "esc" or "ctrl+c"
+pub struct KeyBinding {
+    code: KeyCode,           // The pressed key
+    modifiers: KeyModifiers, // The modifiers which have to be held
+}
+
+impl KeyBinding {
+    // Get a KeyBinding from its name, or None if this name was not found
+    fn from_name(name: &str) -> Option<Self> {
+        let (modifiers, key) = match name.split_once('+') {
+            Some((modifier, key)) if modifier.eq_ignore_ascii_case("ctrl") => {
+                (KeyModifiers::CONTROL, key)
+            }
+            Some((modifier, key)) if modifier.eq_ignore_ascii_case("alt") => {
+                (KeyModifiers::ALT, key)
+            }
+            _ => (KeyModifiers::NONE, name),
+        };
+
+        let code = match key.to_lowercase().as_str() {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            _ => {
+                let mut chars = key.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        };
+
+        Some(Self { code, modifiers })
+    }
+
+    // Get the name of the key like it is written in the config
+    pub fn name(&self) -> String {
+        let key = match self.code {
+            KeyCode::Esc => "esc".to_string(),
+            KeyCode::Enter => "enter".to_string(),
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Tab => "tab".to_string(),
+            KeyCode::Backspace => "backspace".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            code => format!("{:?}", code).to_lowercase(),
+        };
+
+        match self.modifiers {
+            KeyModifiers::CONTROL => format!("ctrl+{}", key),
+            KeyModifiers::ALT => format!("alt+{}", key),
+            _ => key,
+        }
+    }
+
+    // Check if a key event presses this key with its modifiers
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        key_event.code == self.code && key_event.modifiers.contains(self.modifiers)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyBinding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+
+        Self::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown key '{}'", name)))
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+// Commands which are only run on a specific OS
+pub struct CommandSet {
+    #[serde(default)]
+    start_commands: Vec<String>, // Commands run when starting brb
+    #[serde(default)]
+    exit_commands: Vec<String>, // Commands run when exiting brb
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+// The command sets for each OS, configured as "[commands.linux]" and so on
+pub struct OsCommands {
+    linux: Option<CommandSet>,
+    windows: Option<CommandSet>,
+    macos: Option<CommandSet>,
+}
+
+impl OsCommands {
+    // Get the command set of the current OS, if one was configured
+    fn current(&self) -> Option<&CommandSet> {
+        if cfg!(target_os = "linux") {
+            self.linux.as_ref()
+        } else if cfg!(target_os = "windows") {
+            self.windows.as_ref()
+        } else if cfg!(target_os = "macos") {
+            self.macos.as_ref()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "notifications")]
+#[derive(Debug, Deserialize, Clone, Default)]
+// Milestones at which desktop notifications are sent, configured as "[notifications]"
+pub struct Notifications {
+    #[serde(default)]
+    pub remaining_secs: Vec<u64>, // Remaining seconds at which a notification is sent
+    #[serde(default)]
+    pub halfway: bool, // Whether a notification is sent when half of the time has elapsed
+    #[serde(default)]
+    pub finished: bool, // Whether a notification is sent when the countdown finishes
+}
+
 /*
 * The default values are set here
 */
 const DEFAULT_COLOR: &str = "white";
+const DEFAULT_COLOR_DEPTH: Option<ColorDepth> = None;
 const DEFAULT_TEXT: &str = "Be right back";
+const DEFAULT_FINISHED_TEXT: Option<String> = None;
+const DEFAULT_HIDE_TEXT: bool = false;
+const DEFAULT_TRIM_TEXT: bool = true;
+const DEFAULT_ROTATING_TEXT: Vec<String> = vec![];
+const DEFAULT_TEXT_ROTATE_SECS: u64 = 10;
 const DEFAULT_TWITCH_CHANNEL: Option<String> = None;
+#[cfg(feature = "youtube")]
+const DEFAULT_YOUTUBE_VIDEO_ID: Option<String> = None;
 const DEFAULT_CHAT: bool = false;
+const DEFAULT_REMEMBER_CHANNEL: bool = false;
+const DEFAULT_SHOW_CHANNEL: bool = false;
+const DEFAULT_SHOW_FOOTER: bool = false;
+const DEFAULT_SHOW_CLOCK: bool = false;
+const DEFAULT_CHAT_BOLD_NAMES: bool = false;
+const DEFAULT_CHAT_MAX_WIDTH: Option<u16> = None;
+const DEFAULT_CHAT_MESSAGE_SPACING: u16 = 0;
+const DEFAULT_CHAT_GROUP_SENDERS: bool = false;
+const DEFAULT_CHAT_TIMESTAMPS: bool = false;
+const DEFAULT_CHAT_BADGES: bool = false;
+const DEFAULT_CHAT_IGNORE_USERS: Vec<String> = vec![];
+const DEFAULT_CHAT_IGNORE_WORDS: Vec<String> = vec![];
+const DEFAULT_CHAT_ZEBRA: bool = false;
+const DEFAULT_CHAT_ZEBRA_COLOR: Option<TomlColor> = None;
+const DEFAULT_CHAT_LABEL: &str = "chat";
+const DEFAULT_CHAT_TITLE: bool = true;
+const DEFAULT_CHAT_BORDER: bool = true;
+const DEFAULT_CHAT_REVEAL_RATE: Option<usize> = None;
+const DEFAULT_CHAT_ACTIVITY_GRAPH: bool = false;
+const DEFAULT_CHAT_SCROLL_SYMBOL: &str = "▲";
+const DEFAULT_CHAT_SCROLL_COLOR: Option<TomlColor> = None;
+const DEFAULT_CHAT_EXIT_COMMAND: Option<String> = None;
+const DEFAULT_CHAT_EXIT_USERS: Vec<String> = vec![];
+const DEFAULT_CHAT_EXIT_ALLOW_MODS: bool = true;
+const DEFAULT_CHAT_PAUSE_COMMAND: Option<String> = None;
+const DEFAULT_CHAT_RESUME_COMMAND: Option<String> = None;
+const DEFAULT_CHAT_PAUSE_SECS: u64 = 300;
+const DEFAULT_CHAT_PAUSE_USERS: Vec<String> = vec![];
+const DEFAULT_CHAT_PAUSE_ALLOW_MODS: bool = true;
+const DEFAULT_CHAT_LOG: Option<PathBuf> = None;
+const DEFAULT_CHAT_HTML: Option<PathBuf> = None;
+const DEFAULT_CHAT_COLOR_REWARD: Option<String> = None;
 const DEFAULT_SONG_DISPLAY: bool = false;
+const DEFAULT_SONG_POSITION: SongPosition = SongPosition::Top;
+const DEFAULT_SONG_MIN_DISPLAY_MS: u64 = 1500;
+const DEFAULT_SONG_STRIP_FORMATTING: bool = true;
+const DEFAULT_SONG_SHOW_NEXT: bool = false;
+const DEFAULT_SONG_NEXT_COMMAND: Option<String> = None;
+const DEFAULT_SONG_COMMAND: &str = "sc current";
+const DEFAULT_SONG_PLAYER: Option<String> = None;
+const DEFAULT_SONG_ARGS: Vec<String> = vec![];
+const DEFAULT_SONG_PROGRESS: bool = false;
+const DEFAULT_SONG_PROGRESS_COMMAND: &str =
+    "playerctl metadata --format '{{position}} {{mpris:length}}'";
 const DEFAULT_HIDE_TIMER: bool = true;
+const DEFAULT_NO_TIMER: bool = false;
+const DEFAULT_START_DELAY_SECS: u64 = 0;
+const DEFAULT_PRESETS: BTreeMap<String, String> = BTreeMap::new();
+const DEFAULT_PERSIST_TIMER: bool = false;
+const DEFAULT_SERVE_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const DEFAULT_CONFIRM_START: bool = false;
+const DEFAULT_CONFIRM_START_SECS: u64 = 5;
+const DEFAULT_SESSION_LENGTH_SECS: Option<u64> = None;
 const DEFAULT_PROGRESS_BAR: bool = true;
+const DEFAULT_PROGRESS_STYLE: ProgressStyle = ProgressStyle::Bar;
+const DEFAULT_PROGRESS_INVERT: bool = false;
+const DEFAULT_PROGRESS_BG: Option<TomlColor> = None;
+const DEFAULT_PROGRESS_HIDE_NEAR_END: bool = false;
+const DEFAULT_ALERT_THRESHOLD: u64 = 10;
+const DEFAULT_COUNTDOWN_FLASH_SECS: u16 = 0;
+const DEFAULT_DIM_ON_FINISH: bool = false;
+const DEFAULT_BREATHE: bool = false;
 const DEFAULT_PADDING: u16 = 1;
+const DEFAULT_CONTENT_VALIGN: VerticalAlign = VerticalAlign::Center;
+const DEFAULT_LAYOUT: Option<Vec<LayoutElement>> = None;
+const DEFAULT_PAUSE_WHEN_UNFOCUSED: bool = false;
+const DEFAULT_HOT_RELOAD: bool = false;
+const DEFAULT_PAD_MINUTES: bool = true;
+const DEFAULT_TIMER_ROUND: TimerRound = TimerRound::Ceil;
+const DEFAULT_CLOCK_SOURCE: ClockSource = ClockSource::Monotonic;
+const DEFAULT_TIMER_STYLE: TimerStyle = TimerStyle::Big;
+const DEFAULT_TIMER_FONT: TimerFont = TimerFont::Block;
+const DEFAULT_TIMER_SIZE: BigTextSize = BigTextSize::Full;
+const DEFAULT_TEXT_SIZE: BigTextSize = BigTextSize::Quadrant;
+const DEFAULT_TIMER_WORDS: bool = false;
+const DEFAULT_TIMER_DECIMAL: bool = false;
+const DEFAULT_TIMER_DECIMAL_PLACES: usize = 2;
+const DEFAULT_DIGIT_ANIMATION: bool = false;
 const DEFAULT_START_COMMANDS: Vec<String> = vec![];
 const DEFAULT_EXIT_COMMANDS: Vec<String> = vec![];
+const DEFAULT_START_SCRIPT: Option<PathBuf> = None;
+const DEFAULT_EXIT_SCRIPT: Option<PathBuf> = None;
+const DEFAULT_EXIT_COMMANDS_ON: ExitCommandsOn = ExitCommandsOn::Always;
+const DEFAULT_PHASE_COMMANDS: Vec<String> = vec![];
+const DEFAULT_TIME_TRIGGERS: BTreeMap<String, String> = BTreeMap::new();
+const DEFAULT_NEXT_PHASE_KEY: char = 'n';
+const DEFAULT_QUIT_KEY: KeyBinding = KeyBinding {
+    code: KeyCode::Char('q'),
+    modifiers: KeyModifiers::NONE,
+};
+const DEFAULT_CONFIRM_QUIT: bool = false;
+const DEFAULT_TIME_STEP: u64 = 30;
+const DEFAULT_STATUS_FILE: Option<PathBuf> = None;
+const DEFAULT_NON_TTY: NonTtyMode = NonTtyMode::Error;
+#[cfg(feature = "scripting")]
+const DEFAULT_SCRIPT: Option<PathBuf> = None;
+#[cfg(feature = "image")]
+const DEFAULT_IMAGE: Option<PathBuf> = None;
+#[cfg(feature = "qr")]
+const DEFAULT_QR: Option<String> = None;
+#[cfg(feature = "sound")]
+const DEFAULT_FINISHED_SOUND: Option<PathBuf> = None;
+#[cfg(feature = "notifications")]
+const DEFAULT_NOTIFICATIONS: Option<Notifications> = None;
 
 // Implement Default by calling the default_color() function. We have to do this, because this will
 // be used in case the deserialization fails.
@@ -53,6 +613,83 @@ impl Default for TomlColor {
     }
 }
 
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for SongPosition {
+    fn default() -> Self {
+        default_song_position()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for ProgressStyle {
+    fn default() -> Self {
+        default_progress_style()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for TimerFont {
+    fn default() -> Self {
+        default_timer_font()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for BigTextSize {
+    fn default() -> Self {
+        default_timer_size()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for TimerStyle {
+    fn default() -> Self {
+        default_timer_style()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for VerticalAlign {
+    fn default() -> Self {
+        default_content_valign()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for TimerRound {
+    fn default() -> Self {
+        default_timer_round()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for ClockSource {
+    fn default() -> Self {
+        default_clock_source()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for ExitCommandsOn {
+    fn default() -> Self {
+        default_exit_commands_on()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for KeyBinding {
+    fn default() -> Self {
+        default_quit_key()
+    }
+}
+
+// Same as for TomlColor, this is used in case the deserialization fails
+impl Default for NonTtyMode {
+    fn default() -> Self {
+        default_non_tty()
+    }
+}
+
 /*
 * These are the configuration values for the program.
 *
@@ -67,24 +704,242 @@ pub struct Config {
     #[serde(default = "default_color")]
     #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
     color: TomlColor, // The UI accent color
+    #[serde(default = "default_color_depth")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    color_depth: Option<ColorDepth>, // Force the supported colors instead of detecting them
     #[serde(default = "default_text")]
     text: String, // The text which is displayed in the middle
+    #[serde(default = "default_finished_text")]
+    finished_text: Option<String>, // Text displayed instead once the countdown finished
+    #[serde(default = "default_hide_text")]
+    hide_text: bool, // Whether to hide the text
+    #[serde(default = "default_trim_text")]
+    trim_text: bool, // Whether to remove blank lines around the text
+    #[serde(default = "default_rotating_text")]
+    rotating_text: Vec<String>, // Texts which are displayed in turn instead of the text
+    #[serde(default = "default_text_rotate_secs")]
+    text_rotate_secs: u64, // How long each rotating text is displayed
     #[serde(default = "default_twitch_channel")]
     twitch_channel: Option<String>,
+    #[cfg(feature = "youtube")]
+    #[serde(default = "default_youtube_video_id")]
+    youtube_video_id: Option<String>, // The YouTube live stream whose chat is displayed instead of Twitch
+    #[serde(default = "default_remember_channel")]
+    remember_channel: bool, // Whether to fall back to the last used Twitch channel
+    #[serde(default = "default_show_channel")]
+    show_channel: bool, // Whether the Twitch channel is displayed while the chat is off
+    #[serde(default = "default_show_footer")]
+    show_footer: bool, // Whether the version and session runtime are displayed at the bottom
+    #[serde(default = "default_show_clock")]
+    show_clock: bool, // Whether the current time of day is displayed
     #[serde(default = "default_chat")]
     chat: bool, // Whether to display the chat
+    #[serde(default = "default_chat_bold_names")]
+    chat_bold_names: bool, // Whether to render chat sender names bold
+    #[serde(default = "default_chat_max_width")]
+    chat_max_width: Option<u16>, // The maximum width of the chat in characters
+    #[serde(default = "default_chat_message_spacing")]
+    chat_message_spacing: u16, // Blank lines between chat messages
+    #[serde(default = "default_chat_group_senders")]
+    chat_group_senders: bool, // Whether consecutive messages of a sender are grouped under one name
+    #[serde(default = "default_chat_timestamps")]
+    chat_timestamps: bool, // Whether chat messages are prefixed with the time they were received
+    #[serde(default = "default_chat_badges")]
+    chat_badges: bool, // Whether badges of moderators, VIPs and the broadcaster are shown
+    #[serde(default = "default_chat_ignore_users")]
+    chat_ignore_users: Vec<String>, // Chat users whose messages are dropped
+    #[serde(default = "default_chat_ignore_words")]
+    chat_ignore_words: Vec<String>, // Whole words which drop the chat messages containing them
+    #[serde(default = "default_chat_zebra")]
+    chat_zebra: bool, // Whether every other chat message has a shaded background
+    #[serde(default = "default_chat_zebra_color")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    chat_zebra_color: Option<TomlColor>, // The background of shaded chat messages, derived if unset
+    #[serde(default = "default_chat_label")]
+    chat_label: String, // The label at the bottom of the chat, empty to omit it
+    #[serde(default = "default_chat_title")]
+    chat_title: bool, // Whether the channel name is displayed at the top of the chat
+    #[serde(default = "default_chat_border")]
+    chat_border: bool, // Whether the border around the chat is drawn
+    #[serde(default = "default_chat_reveal_rate")]
+    chat_reveal_rate: Option<usize>, // Maximum amount of chat messages revealed per tick
+    #[serde(default = "default_chat_activity_graph")]
+    chat_activity_graph: bool, // Whether to display a graph of the chat activity
+    #[serde(default = "default_chat_scroll_symbol")]
+    chat_scroll_symbol: String, // The symbol in front of the chat scroll indicator
+    #[serde(default = "default_chat_scroll_color")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    chat_scroll_color: Option<TomlColor>, // The color of the chat scroll indicator, accent if unset
+    #[serde(default = "default_chat_exit_command")]
+    chat_exit_command: Option<String>, // Chat command which exits brb
+    #[serde(default = "default_chat_exit_users")]
+    chat_exit_users: Vec<String>, // Users which are allowed to use the exit command
+    #[serde(default = "default_chat_exit_allow_mods")]
+    chat_exit_allow_mods: bool, // Whether moderators are allowed to use the exit command
+    #[serde(default = "default_chat_pause_command")]
+    chat_pause_command: Option<String>, // Chat command which pauses the countdown
+    #[serde(default = "default_chat_resume_command")]
+    chat_resume_command: Option<String>, // Chat command which resumes a paused countdown
+    #[serde(default = "default_chat_pause_secs")]
+    chat_pause_secs: u64, // How long a chat command pauses the countdown
+    #[serde(default = "default_chat_pause_users")]
+    chat_pause_users: Vec<String>, // Users which are allowed to use the pause commands
+    #[serde(default = "default_chat_pause_allow_mods")]
+    chat_pause_allow_mods: bool, // Whether moderators are allowed to use the pause commands
+    #[serde(default = "default_chat_log")]
+    chat_log: Option<PathBuf>, // File received chat messages are appended to
+    #[serde(default = "default_chat_html")]
+    chat_html: Option<PathBuf>, // File the recent chat messages are mirrored to as HTML
+    #[serde(default = "default_chat_color_reward")]
+    chat_color_reward: Option<String>, // Channel-point reward id whose text changes the accent color
     #[serde(default = "default_song_display")]
     song_display: bool, // Whether to display the current song
+    #[serde(default = "default_song_position")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    song_position: SongPosition, // Where the current song is displayed
+    #[serde(default = "default_song_min_display_ms")]
+    song_min_display_ms: u64, // Minimum time a song is displayed before being replaced
+    #[serde(default = "default_song_strip_formatting")]
+    song_strip_formatting: bool, // Whether to strip ANSI and control characters from the song
+    #[serde(default = "default_song_show_next")]
+    song_show_next: bool, // Whether the next song is displayed below the current one
+    #[serde(default = "default_song_next_command")]
+    song_next_command: Option<String>, // Command which outputs the next song
+    #[serde(default = "default_song_command")]
+    song_command: String, // Command which outputs the current song
+    #[serde(default = "default_song_player")]
+    song_player: Option<String>, // Media player whose song is displayed, instead of the song command
+    #[serde(default = "default_song_args")]
+    song_args: Vec<String>, // Extra arguments appended to the song command
+    #[serde(default = "default_song_progress")]
+    song_progress: bool, // Whether a progress bar of the current song is displayed
+    #[serde(default = "default_song_progress_command")]
+    song_progress_command: String, // Command which outputs the song position and length
     #[serde(default = "default_hide_timer")]
     hide_timer: bool, // Whether to hide the countdown when it's done
+    #[serde(default = "default_no_timer")]
+    no_timer: bool, // Whether to disable the countdown entirely
+    #[serde(default = "default_start_delay_secs")]
+    start_delay_secs: u64, // How long to wait before the countdown starts ticking
+    #[serde(default = "default_presets")]
+    presets: BTreeMap<String, String>, // Named durations, selectable instead of time arguments
+    #[serde(default = "default_persist_timer")]
+    persist_timer: bool, // Whether the countdown is resumed after a restart
+    #[serde(default = "default_serve_address")]
+    serve_address: IpAddr, // Address the status server listens on
+    #[serde(default = "default_confirm_start")]
+    confirm_start: bool, // Whether a confirmation screen is shown before the countdown starts
+    #[serde(default = "default_confirm_start_secs")]
+    confirm_start_secs: u64, // How long the confirmation screen waits for a key before starting anyway
+    #[serde(default = "default_session_length_secs")]
+    session_length_secs: Option<u64>, // The base length percentage time arguments refer to
     #[serde(default = "default_progress_bar")]
     progress_bar: bool, // Whether to display the progress bar
+    #[serde(default = "default_progress_style")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    progress_style: ProgressStyle, // How the progress is displayed
+    #[serde(default = "default_progress_invert")]
+    progress_invert: bool, // Whether the progress drains instead of filling up
+    #[serde(default = "default_progress_bg")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    progress_bg: Option<TomlColor>, // The color of the unfilled part of the progress bar
+    #[serde(default = "default_progress_hide_near_end")]
+    progress_hide_near_end: bool, // Whether the progress bar is hidden in the final seconds
+    #[serde(default = "default_alert_threshold")]
+    alert_threshold: u64, // Remaining seconds from which on the final seconds begin
+    #[serde(default = "default_countdown_flash_secs")]
+    countdown_flash_secs: u16, // Remaining seconds from which on the timer flashes, 0 disables it
+    #[serde(default = "default_dim_on_finish")]
+    dim_on_finish: bool, // Whether everything is dimmed once the countdown finishes
+    #[serde(default = "default_breathe")]
+    breathe: bool, // Whether the timer color slowly lightens and darkens
     #[serde(default = "default_padding")]
     padding: u16, // The amount of outer padding
+    #[serde(default = "default_content_valign")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    content_valign: VerticalAlign, // The vertical alignment of the content
+    #[serde(default = "default_layout", deserialize_with = "deserialize_layout")]
+    layout: Option<Vec<LayoutElement>>, // The order of the vertically stacked elements
+    #[serde(default = "default_pause_when_unfocused")]
+    pause_when_unfocused: bool, // Whether to reduce redraws while the terminal is unfocused
+    #[serde(default = "default_hot_reload")]
+    hot_reload: bool, // Whether changes to the config file are applied while running
+    #[serde(default = "default_pad_minutes")]
+    pad_minutes: bool, // Whether to zero-pad the minutes of the timer
+    #[serde(default = "default_timer_round")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    timer_round: TimerRound, // How sub-second remaining time is rounded for display
+    #[serde(default = "default_clock_source")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    clock_source: ClockSource, // The clock which measures the elapsed countdown time
+    #[serde(default = "default_timer_style")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    timer_style: TimerStyle, // How the timer is displayed
+    #[serde(default = "default_timer_font")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    timer_font: TimerFont, // The font of the big timer digits
+    #[serde(default = "default_timer_size")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    timer_size: BigTextSize, // The pixel size of the big timer digits
+    #[serde(default = "default_text_size")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    text_size: Option<BigTextSize>, // The pixel size of the text, None if it is invalid
+    #[serde(default = "default_timer_words")]
+    timer_words: bool, // Whether to display the timer as words
+    #[serde(default = "default_timer_decimal")]
+    timer_decimal: bool, // Whether the time is displayed in decimal hours like 0.25h
+    #[serde(default = "default_timer_decimal_places")]
+    timer_decimal_places: usize, // Decimal places of the time in decimal hours
+    #[serde(default = "default_digit_animation")]
+    digit_animation: bool, // Whether the timer digits roll over to their next value
     #[serde(default = "default_start_commands")]
     start_commands: Vec<String>, // Commands run when starting brb
     #[serde(default = "default_exit_commands")]
     exit_commands: Vec<String>, // Commands run when exiting brb
+    #[serde(default = "default_start_script")]
+    start_script: Option<PathBuf>, // Shell script run when starting brb
+    #[serde(default = "default_exit_script")]
+    exit_script: Option<PathBuf>, // Shell script run when exiting brb
+    #[serde(default = "default_exit_commands_on")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    exit_commands_on: ExitCommandsOn, // When the exit commands and script run
+    #[serde(default = "default_phase_commands")]
+    phase_commands: Vec<String>, // Commands run when a pomodoro phase changes
+    #[serde(default = "default_time_triggers")]
+    time_triggers: BTreeMap<String, String>, // Commands run at remaining seconds thresholds
+    #[serde(default = "default_next_phase_key")]
+    next_phase_key: char, // The key which skips to the next pomodoro phase
+    #[serde(default = "default_quit_key")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    quit_key: KeyBinding, // The key which exits brb
+    #[serde(default = "default_confirm_quit")]
+    confirm_quit: bool, // Whether the quit key has to be pressed twice to exit
+    #[serde(default = "default_time_step")]
+    time_step: u64, // How many seconds the time keys add to or remove from the countdown
+    #[serde(default)]
+    commands: OsCommands, // Commands which are only run on a specific OS
+    #[serde(default = "default_status_file")]
+    status_file: Option<PathBuf>, // File the remaining time is written to every second
+    #[serde(default = "default_non_tty")]
+    #[serde_as(deserialize_as = "DefaultOnError")] // Use default if deserialization fails
+    non_tty: NonTtyMode, // What happens if stdout isn't a terminal
+    #[cfg(feature = "scripting")]
+    #[serde(default = "default_script")]
+    script: Option<PathBuf>, // Path to a Rhai script which is run every tick
+    #[cfg(feature = "image")]
+    #[serde(default = "default_image")]
+    image: Option<PathBuf>, // Path to an image which is displayed above the timer
+    #[cfg(feature = "qr")]
+    #[serde(default = "default_qr")]
+    qr: Option<String>, // Data which is displayed as a QR code
+    #[cfg(feature = "sound")]
+    #[serde(default = "default_finished_sound")]
+    finished_sound: Option<PathBuf>, // Path to a sound file played when the countdown finishes
+    #[cfg(feature = "notifications")]
+    #[serde(default = "default_notifications")]
+    notifications: Option<Notifications>, // Milestones at which desktop notifications are sent
+    #[serde(skip)]
+    warnings: Vec<String>, // Problems of the config file, which are reported before starting
 }
 
 // This function will return the default color
@@ -92,123 +947,890 @@ fn default_color() -> TomlColor {
     TomlColor::Name(DEFAULT_COLOR.to_string())
 }
 
+// This function will return the default color depth
+fn default_color_depth() -> Option<ColorDepth> {
+    DEFAULT_COLOR_DEPTH
+}
+
 // This function will return the default text
 fn default_text() -> String {
     DEFAULT_TEXT.to_string()
 }
 
+// This function will return the default finished text
+fn default_finished_text() -> Option<String> {
+    DEFAULT_FINISHED_TEXT
+}
+
+// This function will return the default hide text
+fn default_hide_text() -> bool {
+    DEFAULT_HIDE_TEXT
+}
+
+// This function will return the default trim text
+fn default_trim_text() -> bool {
+    DEFAULT_TRIM_TEXT
+}
+
+// This function will return the default rotating text
+fn default_rotating_text() -> Vec<String> {
+    DEFAULT_ROTATING_TEXT
+}
+
+// This function will return the default text rotate seconds
+fn default_text_rotate_secs() -> u64 {
+    DEFAULT_TEXT_ROTATE_SECS
+}
+
 // This function will return the default twitch channel
 fn default_twitch_channel() -> Option<String> {
     DEFAULT_TWITCH_CHANNEL
 }
 
+// This function will return the default youtube video id
+#[cfg(feature = "youtube")]
+fn default_youtube_video_id() -> Option<String> {
+    DEFAULT_YOUTUBE_VIDEO_ID
+}
+
+// This function will return the default remember channel
+fn default_remember_channel() -> bool {
+    DEFAULT_REMEMBER_CHANNEL
+}
+
+// This function will return the default show channel value
+fn default_show_channel() -> bool {
+    DEFAULT_SHOW_CHANNEL
+}
+
+// This function will return the default show footer
+fn default_show_footer() -> bool {
+    DEFAULT_SHOW_FOOTER
+}
+
+// This function will return the default show clock
+fn default_show_clock() -> bool {
+    DEFAULT_SHOW_CLOCK
+}
+
 // This function will return the default chat
 fn default_chat() -> bool {
     DEFAULT_CHAT
 }
 
-// This function will return the default song display
-fn default_song_display() -> bool {
-    DEFAULT_SONG_DISPLAY
+// This function will return the default chat bold names
+fn default_chat_bold_names() -> bool {
+    DEFAULT_CHAT_BOLD_NAMES
 }
 
-// This function will return the default hide timer
-fn default_hide_timer() -> bool {
-    DEFAULT_HIDE_TIMER
+// This function will return the default chat max width
+fn default_chat_max_width() -> Option<u16> {
+    DEFAULT_CHAT_MAX_WIDTH
 }
 
-// This function will return the default progress bar
-fn default_progress_bar() -> bool {
-    DEFAULT_PROGRESS_BAR
+// This function will return the default chat message spacing
+fn default_chat_message_spacing() -> u16 {
+    DEFAULT_CHAT_MESSAGE_SPACING
 }
 
-// This function will return the default padding
-fn default_padding() -> u16 {
-    DEFAULT_PADDING
+// This function will return the default chat group senders
+fn default_chat_group_senders() -> bool {
+    DEFAULT_CHAT_GROUP_SENDERS
 }
 
-// This function will return the default start commands
-fn default_start_commands() -> Vec<String> {
-    DEFAULT_START_COMMANDS
+// This function will return the default chat timestamps
+fn default_chat_timestamps() -> bool {
+    DEFAULT_CHAT_TIMESTAMPS
 }
 
-// This function will return the default start commands
-fn default_exit_commands() -> Vec<String> {
-    DEFAULT_EXIT_COMMANDS
+// This function will return the default chat badges
+fn default_chat_badges() -> bool {
+    DEFAULT_CHAT_BADGES
 }
 
-// Get default config
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            color: default_color(),
-            text: default_text(),
-            twitch_channel: default_twitch_channel(),
-            chat: default_chat(),
-            song_display: default_song_display(),
-            hide_timer: default_hide_timer(),
-            progress_bar: default_progress_bar(),
-            padding: default_padding(),
-            start_commands: default_start_commands(),
-            exit_commands: default_exit_commands(),
-        }
-    }
+// This function will return the default ignored chat users
+fn default_chat_ignore_users() -> Vec<String> {
+    DEFAULT_CHAT_IGNORE_USERS
 }
 
-impl Config {
-    // Load the configuration file
-    pub fn load() -> Result<Self> {
-        let config_dir = Self::get_config_dir().context("Failed getting config directory")?;
+// This function will return the default ignored chat words
+fn default_chat_ignore_words() -> Vec<String> {
+    DEFAULT_CHAT_IGNORE_WORDS
+}
 
-        // If the config file exists
-        if config_dir.is_file() {
-            // Read the file
-            let config_str =
-                fs::read_to_string(config_dir).context("Failed reading config file")?;
+// This function will return the default chat zebra
+fn default_chat_zebra() -> bool {
+    DEFAULT_CHAT_ZEBRA
+}
 
-            // Deserialize it
-            toml::from_str::<Config>(&config_str).context("Failed deserializing configuration file")
-        } else {
-            // Otherwise return the default config
-            Ok(Self::default())
-        }
-    }
+// This function will return the default chat zebra color
+fn default_chat_zebra_color() -> Option<TomlColor> {
+    DEFAULT_CHAT_ZEBRA_COLOR
+}
 
-    pub fn get_config_dir() -> Result<PathBuf> {
-        // Get the OS specific configuration directory
-        let mut config_dir =
-            dirs::config_dir().context("Failed getting OS conventional config directory")?;
+// This function will return the default chat label
+fn default_chat_label() -> String {
+    DEFAULT_CHAT_LABEL.to_string()
+}
 
-        // Append "/brb/brb.toml"
-        config_dir.push("brb");
-        config_dir.push("brb.toml");
+// This function will return the default chat title
+fn default_chat_title() -> bool {
+    DEFAULT_CHAT_TITLE
+}
 
-        Ok(config_dir)
-    }
+// This function will return the default chat border
+fn default_chat_border() -> bool {
+    DEFAULT_CHAT_BORDER
+}
 
-    // Map a color name to an actual Color variant
-    fn map_color_name(name: &str) -> Color {
-        match name {
-            "black" => Color::Black,
-            "red" => Color::Red,
-            "green" => Color::Green,
+// This function will return the default chat reveal rate
+fn default_chat_reveal_rate() -> Option<usize> {
+    DEFAULT_CHAT_REVEAL_RATE
+}
+
+// This function will return the default chat activity graph
+fn default_chat_activity_graph() -> bool {
+    DEFAULT_CHAT_ACTIVITY_GRAPH
+}
+
+// This function will return the default chat scroll indicator symbol
+fn default_chat_scroll_symbol() -> String {
+    DEFAULT_CHAT_SCROLL_SYMBOL.to_string()
+}
+
+// This function will return the default chat scroll indicator color
+fn default_chat_scroll_color() -> Option<TomlColor> {
+    DEFAULT_CHAT_SCROLL_COLOR
+}
+
+// This function will return the default chat exit command
+fn default_chat_exit_command() -> Option<String> {
+    DEFAULT_CHAT_EXIT_COMMAND
+}
+
+// This function will return the default chat exit users
+fn default_chat_exit_users() -> Vec<String> {
+    DEFAULT_CHAT_EXIT_USERS
+}
+
+// This function will return the default chat exit allow mods
+fn default_chat_exit_allow_mods() -> bool {
+    DEFAULT_CHAT_EXIT_ALLOW_MODS
+}
+
+// This function will return the default chat pause command
+fn default_chat_pause_command() -> Option<String> {
+    DEFAULT_CHAT_PAUSE_COMMAND
+}
+
+// This function will return the default chat resume command
+fn default_chat_resume_command() -> Option<String> {
+    DEFAULT_CHAT_RESUME_COMMAND
+}
+
+// This function will return the default chat pause duration
+fn default_chat_pause_secs() -> u64 {
+    DEFAULT_CHAT_PAUSE_SECS
+}
+
+// This function will return the default chat pause users
+fn default_chat_pause_users() -> Vec<String> {
+    DEFAULT_CHAT_PAUSE_USERS
+}
+
+// This function will return the default chat pause allow mods
+fn default_chat_pause_allow_mods() -> bool {
+    DEFAULT_CHAT_PAUSE_ALLOW_MODS
+}
+
+// This function will return the default chat log
+fn default_chat_log() -> Option<PathBuf> {
+    DEFAULT_CHAT_LOG
+}
+
+// This function will return the default chat html mirror
+fn default_chat_html() -> Option<PathBuf> {
+    DEFAULT_CHAT_HTML
+}
+
+// This function will return the default chat color reward
+fn default_chat_color_reward() -> Option<String> {
+    DEFAULT_CHAT_COLOR_REWARD
+}
+
+// This function will return the default song display
+fn default_song_display() -> bool {
+    DEFAULT_SONG_DISPLAY
+}
+
+// This function will return the default song position
+fn default_song_position() -> SongPosition {
+    DEFAULT_SONG_POSITION
+}
+
+// This function will return the default song minimum display time
+fn default_song_min_display_ms() -> u64 {
+    DEFAULT_SONG_MIN_DISPLAY_MS
+}
+
+// This function will return the default song strip formatting
+fn default_song_strip_formatting() -> bool {
+    DEFAULT_SONG_STRIP_FORMATTING
+}
+
+// This function will return the default song show next value
+fn default_song_show_next() -> bool {
+    DEFAULT_SONG_SHOW_NEXT
+}
+
+// This function will return the default song next command
+fn default_song_next_command() -> Option<String> {
+    DEFAULT_SONG_NEXT_COMMAND
+}
+
+// This function will return the default command which outputs the current song
+fn default_song_command() -> String {
+    DEFAULT_SONG_COMMAND.to_string()
+}
+
+// This function will return the default song player
+fn default_song_player() -> Option<String> {
+    DEFAULT_SONG_PLAYER
+}
+
+// This function will return the default song args
+fn default_song_args() -> Vec<String> {
+    DEFAULT_SONG_ARGS
+}
+
+// This function will return the default song progress
+fn default_song_progress() -> bool {
+    DEFAULT_SONG_PROGRESS
+}
+
+// This function will return the default command which outputs the song progress
+fn default_song_progress_command() -> String {
+    DEFAULT_SONG_PROGRESS_COMMAND.to_string()
+}
+
+// This function will return the default hide timer
+fn default_hide_timer() -> bool {
+    DEFAULT_HIDE_TIMER
+}
+
+// This function will return the default no timer
+fn default_no_timer() -> bool {
+    DEFAULT_NO_TIMER
+}
+
+// This function will return the default start delay seconds
+fn default_start_delay_secs() -> u64 {
+    DEFAULT_START_DELAY_SECS
+}
+
+// This function will return the default timer presets
+fn default_presets() -> BTreeMap<String, String> {
+    DEFAULT_PRESETS
+}
+
+// This function will return the default persist timer
+fn default_persist_timer() -> bool {
+    DEFAULT_PERSIST_TIMER
+}
+
+// This function will return the default status server address
+fn default_serve_address() -> IpAddr {
+    DEFAULT_SERVE_ADDRESS
+}
+
+// This function will return the default confirm start
+fn default_confirm_start() -> bool {
+    DEFAULT_CONFIRM_START
+}
+
+// This function will return the default confirmation screen timeout
+fn default_confirm_start_secs() -> u64 {
+    DEFAULT_CONFIRM_START_SECS
+}
+
+// This function will return the default session length
+fn default_session_length_secs() -> Option<u64> {
+    DEFAULT_SESSION_LENGTH_SECS
+}
+
+// This function will return the default progress bar
+fn default_progress_bar() -> bool {
+    DEFAULT_PROGRESS_BAR
+}
+
+// This function will return the default progress style
+fn default_progress_style() -> ProgressStyle {
+    DEFAULT_PROGRESS_STYLE
+}
+
+// This function will return the default progress invert
+fn default_progress_invert() -> bool {
+    DEFAULT_PROGRESS_INVERT
+}
+
+// This function will return the default progress bar background color
+fn default_progress_bg() -> Option<TomlColor> {
+    DEFAULT_PROGRESS_BG
+}
+
+// This function will return the default progress hide near end
+fn default_progress_hide_near_end() -> bool {
+    DEFAULT_PROGRESS_HIDE_NEAR_END
+}
+
+// This function will return the default alert threshold
+fn default_alert_threshold() -> u64 {
+    DEFAULT_ALERT_THRESHOLD
+}
+
+// This function will return the default countdown flash seconds
+fn default_countdown_flash_secs() -> u16 {
+    DEFAULT_COUNTDOWN_FLASH_SECS
+}
+
+// This function will return the default dim on finish value
+fn default_dim_on_finish() -> bool {
+    DEFAULT_DIM_ON_FINISH
+}
+
+// This function will return the default breathe value
+fn default_breathe() -> bool {
+    DEFAULT_BREATHE
+}
+
+// This function will return the default padding
+fn default_padding() -> u16 {
+    DEFAULT_PADDING
+}
+
+// This function will return the default content vertical alignment
+fn default_content_valign() -> VerticalAlign {
+    DEFAULT_CONTENT_VALIGN
+}
+
+// This function will return the default layout
+fn default_layout() -> Option<Vec<LayoutElement>> {
+    DEFAULT_LAYOUT
+}
+
+// Deserialize a layout string like "song | timer | text | progress" into its elements
+fn deserialize_layout<'de, D>(deserializer: D) -> Result<Option<Vec<LayoutElement>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let layout = String::deserialize(deserializer)?;
+
+    layout
+        .split('|')
+        .map(|name| {
+            LayoutElement::from_name(name.trim()).ok_or_else(|| {
+                serde::de::Error::custom(format!(
+                    "unknown layout element '{}', must be 'song', 'timer', 'text', 'progress' or 'channel'",
+                    name.trim()
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+// This function will return the default pause when unfocused
+fn default_pause_when_unfocused() -> bool {
+    DEFAULT_PAUSE_WHEN_UNFOCUSED
+}
+
+// This function will return the default hot reload
+fn default_hot_reload() -> bool {
+    DEFAULT_HOT_RELOAD
+}
+
+// This function will return the default pad minutes
+fn default_pad_minutes() -> bool {
+    DEFAULT_PAD_MINUTES
+}
+
+// This function will return the default timer rounding
+fn default_timer_round() -> TimerRound {
+    DEFAULT_TIMER_ROUND
+}
+
+// This function will return the default clock source
+fn default_clock_source() -> ClockSource {
+    DEFAULT_CLOCK_SOURCE
+}
+
+// This function will return the default timer style
+fn default_timer_style() -> TimerStyle {
+    DEFAULT_TIMER_STYLE
+}
+
+// This function will return the default timer font
+fn default_timer_font() -> TimerFont {
+    DEFAULT_TIMER_FONT
+}
+
+// This function will return the default timer size
+fn default_timer_size() -> BigTextSize {
+    DEFAULT_TIMER_SIZE
+}
+
+// This function will return the default text size
+fn default_text_size() -> Option<BigTextSize> {
+    Some(DEFAULT_TEXT_SIZE)
+}
+
+// This function will return the default timer words
+fn default_timer_words() -> bool {
+    DEFAULT_TIMER_WORDS
+}
+
+// This function will return the default timer decimal
+fn default_timer_decimal() -> bool {
+    DEFAULT_TIMER_DECIMAL
+}
+
+// This function will return the default timer decimal places
+fn default_timer_decimal_places() -> usize {
+    DEFAULT_TIMER_DECIMAL_PLACES
+}
+
+// This function will return the default digit animation value
+fn default_digit_animation() -> bool {
+    DEFAULT_DIGIT_ANIMATION
+}
+
+// This function will return the default start commands
+fn default_start_commands() -> Vec<String> {
+    DEFAULT_START_COMMANDS
+}
+
+// This function will return the default start commands
+fn default_exit_commands() -> Vec<String> {
+    DEFAULT_EXIT_COMMANDS
+}
+
+// This function will return the default start script
+fn default_start_script() -> Option<PathBuf> {
+    DEFAULT_START_SCRIPT
+}
+
+// This function will return the default exit script
+fn default_exit_script() -> Option<PathBuf> {
+    DEFAULT_EXIT_SCRIPT
+}
+
+// This function will return the default exit commands trigger
+fn default_exit_commands_on() -> ExitCommandsOn {
+    DEFAULT_EXIT_COMMANDS_ON
+}
+
+// This function will return the default phase commands
+fn default_phase_commands() -> Vec<String> {
+    DEFAULT_PHASE_COMMANDS
+}
+
+// This function will return the default time triggers
+fn default_time_triggers() -> BTreeMap<String, String> {
+    DEFAULT_TIME_TRIGGERS
+}
+
+// This function will return the default next phase key
+fn default_next_phase_key() -> char {
+    DEFAULT_NEXT_PHASE_KEY
+}
+
+// This function will return the default quit key
+fn default_quit_key() -> KeyBinding {
+    DEFAULT_QUIT_KEY
+}
+
+// This function will return the default confirm quit
+fn default_confirm_quit() -> bool {
+    DEFAULT_CONFIRM_QUIT
+}
+
+// This function will return the default time step
+fn default_time_step() -> u64 {
+    DEFAULT_TIME_STEP
+}
+
+// This function will return the default status file
+fn default_status_file() -> Option<PathBuf> {
+    DEFAULT_STATUS_FILE
+}
+
+// This function will return the default non-TTY mode
+fn default_non_tty() -> NonTtyMode {
+    DEFAULT_NON_TTY
+}
+
+// This function will return the default script
+#[cfg(feature = "scripting")]
+fn default_script() -> Option<PathBuf> {
+    DEFAULT_SCRIPT
+}
+
+// This function will return the default image
+#[cfg(feature = "image")]
+fn default_image() -> Option<PathBuf> {
+    DEFAULT_IMAGE
+}
+
+// This function will return the default QR code data
+#[cfg(feature = "qr")]
+fn default_qr() -> Option<String> {
+    DEFAULT_QR
+}
+
+// This function will return the default finished sound
+#[cfg(feature = "sound")]
+fn default_finished_sound() -> Option<PathBuf> {
+    DEFAULT_FINISHED_SOUND
+}
+
+// This function will return the default notifications
+#[cfg(feature = "notifications")]
+fn default_notifications() -> Option<Notifications> {
+    DEFAULT_NOTIFICATIONS
+}
+
+// Get default config
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            color: default_color(),
+            // Without a config file the supported colors are detected right away, like on load
+            color_depth: Some(ColorDepth::detect()),
+            text: default_text(),
+            finished_text: default_finished_text(),
+            hide_text: default_hide_text(),
+            trim_text: default_trim_text(),
+            rotating_text: default_rotating_text(),
+            text_rotate_secs: default_text_rotate_secs(),
+            twitch_channel: default_twitch_channel(),
+            #[cfg(feature = "youtube")]
+            youtube_video_id: default_youtube_video_id(),
+            remember_channel: default_remember_channel(),
+            show_channel: default_show_channel(),
+            show_footer: default_show_footer(),
+            show_clock: default_show_clock(),
+            chat: default_chat(),
+            chat_bold_names: default_chat_bold_names(),
+            chat_max_width: default_chat_max_width(),
+            chat_message_spacing: default_chat_message_spacing(),
+            chat_group_senders: default_chat_group_senders(),
+            chat_timestamps: default_chat_timestamps(),
+            chat_badges: default_chat_badges(),
+            chat_ignore_users: default_chat_ignore_users(),
+            chat_ignore_words: default_chat_ignore_words(),
+            chat_zebra: default_chat_zebra(),
+            chat_zebra_color: default_chat_zebra_color(),
+            chat_label: default_chat_label(),
+            chat_title: default_chat_title(),
+            chat_border: default_chat_border(),
+            chat_reveal_rate: default_chat_reveal_rate(),
+            chat_activity_graph: default_chat_activity_graph(),
+            chat_scroll_symbol: default_chat_scroll_symbol(),
+            chat_scroll_color: default_chat_scroll_color(),
+            chat_exit_command: default_chat_exit_command(),
+            chat_exit_users: default_chat_exit_users(),
+            chat_exit_allow_mods: default_chat_exit_allow_mods(),
+            chat_pause_command: default_chat_pause_command(),
+            chat_resume_command: default_chat_resume_command(),
+            chat_pause_secs: default_chat_pause_secs(),
+            chat_pause_users: default_chat_pause_users(),
+            chat_pause_allow_mods: default_chat_pause_allow_mods(),
+            chat_log: default_chat_log(),
+            chat_html: default_chat_html(),
+            chat_color_reward: default_chat_color_reward(),
+            song_display: default_song_display(),
+            song_position: default_song_position(),
+            song_min_display_ms: default_song_min_display_ms(),
+            song_strip_formatting: default_song_strip_formatting(),
+            song_show_next: default_song_show_next(),
+            song_next_command: default_song_next_command(),
+            song_command: default_song_command(),
+            song_player: default_song_player(),
+            song_args: default_song_args(),
+            song_progress: default_song_progress(),
+            song_progress_command: default_song_progress_command(),
+            hide_timer: default_hide_timer(),
+            no_timer: default_no_timer(),
+            start_delay_secs: default_start_delay_secs(),
+            presets: default_presets(),
+            persist_timer: default_persist_timer(),
+            serve_address: default_serve_address(),
+            confirm_start: default_confirm_start(),
+            confirm_start_secs: default_confirm_start_secs(),
+            session_length_secs: default_session_length_secs(),
+            progress_bar: default_progress_bar(),
+            progress_style: default_progress_style(),
+            progress_invert: default_progress_invert(),
+            progress_bg: default_progress_bg(),
+            progress_hide_near_end: default_progress_hide_near_end(),
+            alert_threshold: default_alert_threshold(),
+            countdown_flash_secs: default_countdown_flash_secs(),
+            dim_on_finish: default_dim_on_finish(),
+            breathe: default_breathe(),
+            padding: default_padding(),
+            content_valign: default_content_valign(),
+            layout: default_layout(),
+            pause_when_unfocused: default_pause_when_unfocused(),
+            hot_reload: default_hot_reload(),
+            pad_minutes: default_pad_minutes(),
+            timer_round: default_timer_round(),
+            clock_source: default_clock_source(),
+            timer_style: default_timer_style(),
+            timer_font: default_timer_font(),
+            timer_size: default_timer_size(),
+            text_size: default_text_size(),
+            timer_words: default_timer_words(),
+            timer_decimal: default_timer_decimal(),
+            timer_decimal_places: default_timer_decimal_places(),
+            digit_animation: default_digit_animation(),
+            start_commands: default_start_commands(),
+            exit_commands: default_exit_commands(),
+            start_script: default_start_script(),
+            exit_script: default_exit_script(),
+            exit_commands_on: default_exit_commands_on(),
+            phase_commands: default_phase_commands(),
+            time_triggers: default_time_triggers(),
+            next_phase_key: default_next_phase_key(),
+            quit_key: default_quit_key(),
+            confirm_quit: default_confirm_quit(),
+            time_step: default_time_step(),
+            commands: OsCommands::default(),
+            status_file: default_status_file(),
+            non_tty: default_non_tty(),
+            #[cfg(feature = "scripting")]
+            script: default_script(),
+            #[cfg(feature = "image")]
+            image: default_image(),
+            #[cfg(feature = "qr")]
+            qr: default_qr(),
+            #[cfg(feature = "sound")]
+            finished_sound: default_finished_sound(),
+            #[cfg(feature = "notifications")]
+            notifications: default_notifications(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    // Load the configuration file
+    pub fn load() -> Result<Self> {
+        Self::load_with_preset(None)
+    }
+
+    // Load the config file on top of the provided preset, which replaces the one in the file
+    pub fn load_with_preset(preset: Option<Preset>) -> Result<Self> {
+        let config_dir = Self::get_config_dir().context("Failed getting config directory")?;
+        Self::load_from(&config_dir, preset)
+    }
+
+    // Load the config file at the provided path on top of the provided preset
+    fn load_from(config_dir: &Path, preset: Option<Preset>) -> Result<Self> {
+        // If the config file exists
+        if config_dir.is_file() {
+            // Read the file
+            let config_str =
+                fs::read_to_string(config_dir).context("Failed reading config file")?;
+
+            // Deserialize it
+            let mut config = Self::from_toml(&config_str, preset)
+                .context("Failed deserializing configuration file")?;
+
+            // Add the commands specific to the current OS
+            config.apply_os_commands();
+
+            // Make sure the configured scripts exist, as errors can't be shown once running
+            config.check_scripts();
+
+            Ok(config)
+        } else if fs::symlink_metadata(config_dir).is_ok() {
+            // Something else like a directory or a broken symlink is in the way of the config file
+            bail!(
+                "Config path '{}' exists, but isn't a readable file",
+                config_dir.display()
+            )
+        } else if preset.is_some() {
+            // Otherwise use the preset values on their own
+            Self::from_toml("", preset)
+        } else {
+            // Otherwise return the default config
+            Ok(Self::default())
+        }
+    }
+
+    // Deserialize the config, where the values of the file override the values of the preset
+    pub(crate) fn from_toml(config_str: &str, preset: Option<Preset>) -> Result<Self> {
+        let mut values = toml::from_str::<toml::Table>(config_str)?;
+
+        // Use the preset from the file, unless another one was provided
+        let file_preset = match values.remove("preset") {
+            Some(toml::Value::String(name)) => match Preset::from_name(&name) {
+                Some(preset) => Some(preset),
+                None => bail!(
+                    "unknown preset '{}', must be 'minimal', 'full' or 'overlay'",
+                    name
+                ),
+            },
+            Some(_) => bail!("the preset must be a string"),
+            None => None,
+        };
+
+        let mut config = values.clone().try_into::<Config>()?;
+
+        if let Some(preset) = preset.or(file_preset) {
+            preset.apply(&mut config, &values);
+        }
+
+        // Only detect the supported colors once, instead of every time a color is used
+        config.color_depth.get_or_insert_with(ColorDepth::detect);
+
+        Ok(config)
+    }
+
+    // Append the commands of the current OS to the common commands
+    fn apply_os_commands(&mut self) {
+        if let Some(commands) = self.commands.current() {
+            self.start_commands
+                .extend(commands.start_commands.iter().cloned());
+            self.exit_commands
+                .extend(commands.exit_commands.iter().cloned());
+        }
+    }
+
+    // Ignore the configured start or exit scripts which don't exist, with a warning
+    fn check_scripts(&mut self) {
+        for script in [&mut self.start_script, &mut self.exit_script] {
+            if let Some(path) = script.take_if(|path| !path.is_file()) {
+                self.warnings.push(format!(
+                    "Script file '{}' doesn't exist, it is ignored",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    // Take the problems which were found while loading the config file
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    pub fn get_config_dir() -> Result<PathBuf> {
+        // If the config directory was overridden, use "$BRB_CONFIG_DIR/brb.toml"
+        if let Some(dir) = env::var_os(CONFIG_DIR_ENV).filter(|d| !d.is_empty()) {
+            return Ok(PathBuf::from(dir).join(CONFIG_FILE));
+        }
+
+        // Get the OS specific configuration directory
+        let mut config_dir =
+            dirs::config_dir().context("Failed getting OS conventional config directory")?;
+
+        // Append "/brb/brb.toml"
+        config_dir.push("brb");
+        config_dir.push(CONFIG_FILE);
+
+        Ok(config_dir)
+    }
+
+    // Get the file the countdown is persisted in, next to the config file
+    pub fn get_timer_state_file() -> Result<PathBuf> {
+        Ok(Self::get_config_dir()?.with_file_name(TIMER_STATE_FILE))
+    }
+
+    // Get the file the last used Twitch channel is stored in, next to the config file
+    fn get_last_channel_file() -> Result<PathBuf> {
+        Ok(Self::get_config_dir()?.with_file_name(LAST_CHANNEL_FILE))
+    }
+
+    // Load the last used Twitch channel, or None if it is missing or invalid
+    pub fn load_last_channel() -> Option<String> {
+        let channel = fs::read_to_string(Self::get_last_channel_file().ok()?).ok()?;
+        let channel = channel.trim();
+
+        // Ignore the file if it doesn't contain a single channel name
+        if channel.is_empty() || channel.contains(char::is_whitespace) {
+            None
+        } else {
+            Some(channel.to_string())
+        }
+    }
+
+    // Fall back to the last used Twitch channel if no channel was configured and it is remembered
+    pub fn apply_last_channel(&mut self) {
+        if self.remember_channel && self.twitch_channel.is_none() {
+            self.twitch_channel = Self::load_last_channel();
+        }
+    }
+
+    // Store the last used Twitch channel, errors are ignored as this is only a convenience
+    pub fn save_last_channel(channel: &str) {
+        if let Ok(path) = Self::get_last_channel_file() {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+
+            let _ = fs::write(path, channel);
+        }
+    }
+
+    // Map a color name to an actual Color variant
+    fn map_color_name(name: &str) -> Color {
+        match name {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
             "yellow" => Color::Yellow,
             "blue" => Color::Blue,
             "magenta" => Color::Magenta,
             "cyan" => Color::Cyan,
             "white" => Color::White,
-            _ => Self::map_color_name(DEFAULT_COLOR),
+            _ => {
+                if let Some((r, g, b)) = extended_color(name) {
+                    Color::Rgb(r, g, b)
+                } else if let Ok(index) = name.parse::<u8>() {
+                    Color::Indexed(index)
+                } else {
+                    Self::map_color_name(DEFAULT_COLOR)
+                }
+            }
         }
     }
 
-    // Get the color from the config
-    pub fn get_color(&self) -> Color {
-        match &self.color {
+    // Map a TomlColor to an actual Color, RGB colors are reduced if the terminal lacks truecolor
+    fn map_toml_color(&self, color: &TomlColor) -> Color {
+        let color = match color {
             // If the color is deserializeable as a RGB color
             TomlColor::Rgb { r, g, b } => Color::Rgb(*r, *g, *b),
+            // If the color is an index of the 256 color palette
+            TomlColor::Indexed(index) => Color::Indexed(*index),
+            // If the color is a hex code, which is also deserialized as a name
+            TomlColor::Name(name) if name.starts_with('#') => match TomlColor::from_hex(name) {
+                Ok(TomlColor::Rgb { r, g, b }) => Color::Rgb(r, g, b),
+                _ => Self::map_color_name(DEFAULT_COLOR),
+            },
             // If the color is a color preset name
             TomlColor::Name(name) => Self::map_color_name(name.to_lowercase().as_str()),
-        }
+        };
+
+        progress::reduce_color(color, self.get_color_depth())
+    }
+
+    // Get the color from the config
+    pub fn get_color(&self) -> Color {
+        self.map_toml_color(&self.color)
+    }
+
+    pub fn get_color_depth(&self) -> ColorDepth {
+        self.color_depth.unwrap_or_else(ColorDepth::detect)
     }
 
     /*
@@ -227,6 +1849,26 @@ impl Config {
         self.text.clone()
     }
 
+    pub fn get_finished_text(&self) -> Option<String> {
+        self.finished_text.clone()
+    }
+
+    pub fn is_hide_text(&self) -> bool {
+        self.hide_text
+    }
+
+    pub fn is_trim_text(&self) -> bool {
+        self.trim_text
+    }
+
+    pub fn get_rotating_text(&self) -> Vec<String> {
+        self.rotating_text.clone()
+    }
+
+    pub fn get_text_rotate_interval(&self) -> Duration {
+        Duration::from_secs(self.text_rotate_secs)
+    }
+
     pub fn set_twitch_channel(&mut self, twitch_channel: String) {
         self.twitch_channel = Some(twitch_channel);
     }
@@ -235,6 +1877,27 @@ impl Config {
         self.twitch_channel.clone()
     }
 
+    #[cfg(feature = "youtube")]
+    pub fn get_youtube_video_id(&self) -> Option<String> {
+        self.youtube_video_id.clone()
+    }
+
+    pub fn is_remember_channel(&self) -> bool {
+        self.remember_channel
+    }
+
+    pub fn is_show_channel(&self) -> bool {
+        self.show_channel
+    }
+
+    pub fn is_show_footer(&self) -> bool {
+        self.show_footer
+    }
+
+    pub fn is_show_clock(&self) -> bool {
+        self.show_clock
+    }
+
     pub fn set_chat(&mut self, chat: bool) {
         self.chat = chat;
     }
@@ -243,6 +1906,122 @@ impl Config {
         self.chat
     }
 
+    pub fn is_chat_bold_names(&self) -> bool {
+        self.chat_bold_names
+    }
+
+    pub fn get_chat_max_width(&self) -> Option<u16> {
+        self.chat_max_width
+    }
+
+    pub fn get_chat_message_spacing(&self) -> u16 {
+        self.chat_message_spacing
+    }
+
+    pub fn is_chat_group_senders(&self) -> bool {
+        self.chat_group_senders
+    }
+
+    pub fn is_chat_timestamps(&self) -> bool {
+        self.chat_timestamps
+    }
+
+    pub fn is_chat_badges(&self) -> bool {
+        self.chat_badges
+    }
+
+    pub fn get_chat_ignore_users(&self) -> Vec<String> {
+        self.chat_ignore_users.clone()
+    }
+
+    pub fn get_chat_ignore_words(&self) -> Vec<String> {
+        self.chat_ignore_words.clone()
+    }
+
+    pub fn is_chat_zebra(&self) -> bool {
+        self.chat_zebra
+    }
+
+    pub fn get_chat_zebra_color(&self) -> Option<Color> {
+        self.chat_zebra_color
+            .as_ref()
+            .map(|color| self.map_toml_color(color))
+    }
+
+    pub fn get_chat_label(&self) -> String {
+        self.chat_label.clone()
+    }
+
+    pub fn is_chat_title(&self) -> bool {
+        self.chat_title
+    }
+
+    pub fn is_chat_border(&self) -> bool {
+        self.chat_border
+    }
+
+    pub fn get_chat_reveal_rate(&self) -> Option<usize> {
+        self.chat_reveal_rate
+    }
+
+    pub fn is_chat_activity_graph(&self) -> bool {
+        self.chat_activity_graph
+    }
+
+    pub fn get_chat_scroll_symbol(&self) -> String {
+        self.chat_scroll_symbol.clone()
+    }
+
+    pub fn get_chat_scroll_color(&self) -> Option<Color> {
+        self.chat_scroll_color
+            .as_ref()
+            .map(|color| self.map_toml_color(color))
+    }
+
+    pub fn get_chat_exit_command(&self) -> Option<String> {
+        self.chat_exit_command.clone()
+    }
+
+    pub fn get_chat_exit_users(&self) -> Vec<String> {
+        self.chat_exit_users.clone()
+    }
+
+    pub fn is_chat_exit_allow_mods(&self) -> bool {
+        self.chat_exit_allow_mods
+    }
+
+    pub fn get_chat_pause_command(&self) -> Option<String> {
+        self.chat_pause_command.clone()
+    }
+
+    pub fn get_chat_resume_command(&self) -> Option<String> {
+        self.chat_resume_command.clone()
+    }
+
+    pub fn get_chat_pause_duration(&self) -> Duration {
+        Duration::from_secs(self.chat_pause_secs)
+    }
+
+    pub fn get_chat_pause_users(&self) -> Vec<String> {
+        self.chat_pause_users.clone()
+    }
+
+    pub fn is_chat_pause_allow_mods(&self) -> bool {
+        self.chat_pause_allow_mods
+    }
+
+    pub fn get_chat_log(&self) -> Option<PathBuf> {
+        self.chat_log.clone()
+    }
+
+    pub fn get_chat_html(&self) -> Option<PathBuf> {
+        self.chat_html.clone()
+    }
+
+    pub fn get_chat_color_reward(&self) -> Option<String> {
+        self.chat_color_reward.clone()
+    }
+
     pub fn set_song_display(&mut self, song_display: bool) {
         self.song_display = song_display;
     }
@@ -251,6 +2030,46 @@ impl Config {
         self.song_display
     }
 
+    pub fn get_song_position(&self) -> SongPosition {
+        self.song_position
+    }
+
+    pub fn get_song_min_display(&self) -> Duration {
+        Duration::from_millis(self.song_min_display_ms)
+    }
+
+    pub fn is_song_strip_formatting(&self) -> bool {
+        self.song_strip_formatting
+    }
+
+    pub fn is_song_show_next(&self) -> bool {
+        self.song_show_next
+    }
+
+    pub fn get_song_next_command(&self) -> Option<String> {
+        self.song_next_command.clone()
+    }
+
+    pub fn get_song_command(&self) -> String {
+        self.song_command.clone()
+    }
+
+    pub fn get_song_player(&self) -> Option<String> {
+        self.song_player.clone()
+    }
+
+    pub fn get_song_args(&self) -> Vec<String> {
+        self.song_args.clone()
+    }
+
+    pub fn is_song_progress(&self) -> bool {
+        self.song_progress
+    }
+
+    pub fn get_song_progress_command(&self) -> String {
+        self.song_progress_command.clone()
+    }
+
     pub fn set_hide_timer(&mut self, hide_timer: bool) {
         self.hide_timer = hide_timer;
     }
@@ -259,6 +2078,46 @@ impl Config {
         self.hide_timer
     }
 
+    pub fn set_show_clock(&mut self, show_clock: bool) {
+        self.show_clock = show_clock;
+    }
+
+    pub fn set_no_timer(&mut self, no_timer: bool) {
+        self.no_timer = no_timer;
+    }
+
+    pub fn is_no_timer(&self) -> bool {
+        self.no_timer
+    }
+
+    pub fn get_start_delay(&self) -> Duration {
+        Duration::from_secs(self.start_delay_secs)
+    }
+
+    pub fn get_presets(&self) -> BTreeMap<String, String> {
+        self.presets.clone()
+    }
+
+    pub fn is_persist_timer(&self) -> bool {
+        self.persist_timer
+    }
+
+    pub fn get_serve_address(&self) -> IpAddr {
+        self.serve_address
+    }
+
+    pub fn is_confirm_start(&self) -> bool {
+        self.confirm_start
+    }
+
+    pub fn get_confirm_start_timeout(&self) -> Duration {
+        Duration::from_secs(self.confirm_start_secs)
+    }
+
+    pub fn get_session_length(&self) -> Option<Duration> {
+        self.session_length_secs.map(Duration::from_secs)
+    }
+
     pub fn set_progress_bar(&mut self, progress_bar: bool) {
         self.progress_bar = progress_bar;
     }
@@ -267,6 +2126,40 @@ impl Config {
         self.progress_bar
     }
 
+    pub fn get_progress_style(&self) -> ProgressStyle {
+        self.progress_style
+    }
+
+    pub fn is_progress_invert(&self) -> bool {
+        self.progress_invert
+    }
+
+    pub fn get_progress_bg(&self) -> Option<Color> {
+        self.progress_bg
+            .as_ref()
+            .map(|color| self.map_toml_color(color))
+    }
+
+    pub fn is_progress_hide_near_end(&self) -> bool {
+        self.progress_hide_near_end
+    }
+
+    pub fn get_alert_threshold(&self) -> Duration {
+        Duration::from_secs(self.alert_threshold)
+    }
+
+    pub fn get_countdown_flash(&self) -> Duration {
+        Duration::from_secs(self.countdown_flash_secs as u64)
+    }
+
+    pub fn is_dim_on_finish(&self) -> bool {
+        self.dim_on_finish
+    }
+
+    pub fn is_breathe(&self) -> bool {
+        self.breathe
+    }
+
     pub fn set_padding(&mut self, padding: u16) {
         self.padding = padding
     }
@@ -275,6 +2168,67 @@ impl Config {
         self.padding
     }
 
+    pub fn get_content_valign(&self) -> VerticalAlign {
+        self.content_valign
+    }
+
+    pub fn get_layout(&self) -> Option<Vec<LayoutElement>> {
+        self.layout.clone()
+    }
+
+    pub fn is_pause_when_unfocused(&self) -> bool {
+        self.pause_when_unfocused
+    }
+
+    pub fn is_hot_reload(&self) -> bool {
+        self.hot_reload
+    }
+
+    pub fn is_pad_minutes(&self) -> bool {
+        self.pad_minutes
+    }
+
+    pub fn get_timer_round(&self) -> TimerRound {
+        self.timer_round
+    }
+
+    pub fn get_clock_source(&self) -> ClockSource {
+        self.clock_source
+    }
+
+    pub fn get_timer_style(&self) -> TimerStyle {
+        self.timer_style
+    }
+
+    pub fn get_timer_font(&self) -> TimerFont {
+        self.timer_font
+    }
+
+    pub fn get_timer_size(&self) -> BigTextSize {
+        self.timer_size
+    }
+
+    // An invalid text size falls back to its own default, instead of the default of BigTextSize
+    pub fn get_text_size(&self) -> BigTextSize {
+        self.text_size.unwrap_or(DEFAULT_TEXT_SIZE)
+    }
+
+    pub fn is_timer_words(&self) -> bool {
+        self.timer_words
+    }
+
+    pub fn is_timer_decimal(&self) -> bool {
+        self.timer_decimal
+    }
+
+    pub fn get_timer_decimal_places(&self) -> usize {
+        self.timer_decimal_places
+    }
+
+    pub fn is_digit_animation(&self) -> bool {
+        self.digit_animation
+    }
+
     pub fn get_start_commands(&self) -> Vec<String> {
         self.start_commands.clone()
     }
@@ -282,4 +2236,188 @@ impl Config {
     pub fn get_exit_commands(&self) -> Vec<String> {
         self.exit_commands.clone()
     }
+
+    pub fn get_start_script(&self) -> Option<PathBuf> {
+        self.start_script.clone()
+    }
+
+    pub fn get_exit_script(&self) -> Option<PathBuf> {
+        self.exit_script.clone()
+    }
+
+    pub fn get_exit_commands_on(&self) -> ExitCommandsOn {
+        self.exit_commands_on
+    }
+
+    pub fn get_phase_commands(&self) -> Vec<String> {
+        self.phase_commands.clone()
+    }
+
+    // Get the thresholds with their commands, keys which aren't a number of seconds are ignored
+    pub fn get_time_triggers(&self) -> Vec<(Duration, String)> {
+        self.time_triggers
+            .iter()
+            .filter_map(|(secs, command)| {
+                let secs = secs.trim().parse::<u64>().ok()?;
+                Some((Duration::from_secs(secs), command.clone()))
+            })
+            .collect()
+    }
+
+    pub fn get_next_phase_key(&self) -> char {
+        self.next_phase_key
+    }
+
+    pub fn get_quit_key(&self) -> KeyBinding {
+        self.quit_key
+    }
+
+    pub fn is_confirm_quit(&self) -> bool {
+        self.confirm_quit
+    }
+
+    pub fn get_time_step(&self) -> Duration {
+        Duration::from_secs(self.time_step)
+    }
+
+    pub fn get_status_file(&self) -> Option<PathBuf> {
+        self.status_file.clone()
+    }
+
+    pub fn get_non_tty(&self) -> NonTtyMode {
+        self.non_tty
+    }
+
+    #[cfg(feature = "scripting")]
+    pub fn get_script(&self) -> Option<PathBuf> {
+        self.script.clone()
+    }
+
+    #[cfg(feature = "image")]
+    pub fn get_image(&self) -> Option<PathBuf> {
+        self.image.clone()
+    }
+
+    #[cfg(feature = "qr")]
+    pub fn get_qr(&self) -> Option<String> {
+        self.qr.clone()
+    }
+
+    #[cfg(feature = "sound")]
+    pub fn get_finished_sound(&self) -> Option<PathBuf> {
+        self.finished_sound.clone()
+    }
+
+    #[cfg(feature = "notifications")]
+    pub fn get_notifications(&self) -> Option<Notifications> {
+        self.notifications.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Create an empty directory for a test, which doesn't clash with the other tests
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("brb-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn missing_file_uses_defaults() {
+        let dir = test_dir("missing");
+        let config = Config::load_from(&dir.join("brb.toml"), None).unwrap();
+
+        assert_eq!(config.get_padding(), DEFAULT_PADDING);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_is_loaded() {
+        let dir = test_dir("file");
+        let path = dir.join("brb.toml");
+        fs::write(&path, "padding = 4").unwrap();
+
+        assert_eq!(Config::load_from(&path, None).unwrap().get_padding(), 4);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn directory_is_an_error() {
+        let dir = test_dir("directory");
+        let path = dir.join("brb.toml");
+        fs::create_dir(&path).unwrap();
+
+        let error = Config::load_from(&path, None).err().unwrap();
+        assert!(error.to_string().contains("isn't a readable file"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_script_is_ignored() {
+        let dir = test_dir("script");
+        let path = dir.join("brb.toml");
+        let script = dir.join("start.sh");
+        fs::write(&script, "echo start").unwrap();
+        fs::write(
+            &path,
+            format!(
+                "start_script = {:?}\nexit_script = {:?}",
+                script,
+                dir.join("missing.sh")
+            ),
+        )
+        .unwrap();
+
+        let mut config = Config::load_from(&path, None).unwrap();
+
+        assert_eq!(config.get_start_script(), Some(script));
+        assert_eq!(config.get_exit_script(), None);
+        assert_eq!(config.take_warnings().len(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn broken_symlink_is_an_error() {
+        let dir = test_dir("symlink");
+        let path = dir.join("brb.toml");
+        std::os::unix::fs::symlink(dir.join("missing.toml"), &path).unwrap();
+
+        assert!(Config::load_from(&path, None).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn every_preset_loads() {
+        let minimal = Config::from_toml("", Some(Preset::Minimal)).unwrap();
+        assert_eq!(minimal.padding, 0);
+        assert!(!minimal.progress_bar);
+        assert!(!minimal.chat_title);
+        assert!(!minimal.chat_border);
+        assert!(minimal.chat_label.is_empty());
+
+        let full = Config::from_toml("", Some(Preset::Full)).unwrap();
+        assert!(full.chat);
+        assert_eq!(full.progress_style, ProgressStyle::Ring);
+
+        let overlay = Config::from_toml("", Some(Preset::Overlay)).unwrap();
+        assert_eq!(overlay.timer_style, TimerStyle::Bar);
+        assert_eq!(overlay.content_valign, VerticalAlign::Top);
+    }
+
+    #[test]
+    fn file_overrides_preset() {
+        let config = Config::from_toml("preset = \"minimal\"\npadding = 3", None).unwrap();
+        assert_eq!(config.padding, 3);
+        assert!(!config.chat_border);
+
+        // A preset from the command line replaces the one in the file
+        let config = Config::from_toml("preset = \"minimal\"", Some(Preset::Full)).unwrap();
+        assert!(config.chat_border);
+        assert!(config.progress_bar);
+    }
 }
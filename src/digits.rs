@@ -0,0 +1,197 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+use tui_big_text::{BigText, PixelSize};
+
+// The width and height of a single big digit in cells
+const GLYPH_SIZE: u16 = 8;
+
+// The big timer digits, where the changing digits roll over to their next value like an odometer
+pub struct RollingDigits {
+    current: String, // The currently displayed time
+    next: String,    // The time which is displayed next
+    progress: f64,   // How far the digits have rolled towards the next time, from 0.0 to 1.0
+    color: Color,    // The color of the digits
+}
+
+impl RollingDigits {
+    pub fn new(current: String, next: String, progress: f64, color: Color) -> Self {
+        Self {
+            current,
+            next,
+            progress: progress.clamp(0.0, 1.0),
+            color,
+        }
+    }
+
+    // Build the BigText widget for a time
+    fn big_text(&self, time: &str) -> BigText<'static> {
+        BigText::builder()
+            .pixel_size(PixelSize::Full)
+            .style(Style::new().fg(self.color))
+            .lines(vec![time.to_string().into()])
+            .centered()
+            .build()
+    }
+}
+
+impl Widget for RollingDigits {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.big_text(&self.current).render(area, buf);
+
+        let length = self.current.chars().count() as u16;
+
+        // Only roll if the digits line up, which isn't the case if the length changes
+        if self.progress <= 0.0 || length != self.next.chars().count() as u16 {
+            return;
+        }
+
+        // Render the next time separately, so its rows can be scrolled in from below
+        let mut next_buf = Buffer::empty(area);
+        self.big_text(&self.next).render(area, &mut next_buf);
+
+        // The same buffer the rows of the current time are read from, so they aren't overwritten
+        let current_buf = buf.clone();
+
+        let offset = (self.progress * GLYPH_SIZE as f64).round() as u16;
+        let height = area.height.min(GLYPH_SIZE);
+
+        // Same as BigText, the digits are centered in the area
+        let start = area.x + (area.width / 2).saturating_sub(length * GLYPH_SIZE / 2);
+
+        let changed = self
+            .current
+            .chars()
+            .zip(self.next.chars())
+            .enumerate()
+            .filter(|(_, (current, next))| current != next);
+
+        for (index, _) in changed {
+            let glyph_x = start + index as u16 * GLYPH_SIZE;
+
+            for x in glyph_x..(glyph_x + GLYPH_SIZE).min(area.right()) {
+                for row in 0..height {
+                    // Rows of the current digit move up, followed by the rows of the next digit
+                    let source = row + offset;
+                    let cell = if source < GLYPH_SIZE {
+                        current_buf.cell((x, area.y + source))
+                    } else {
+                        next_buf.cell((x, area.y + source - GLYPH_SIZE))
+                    };
+
+                    if let (Some(cell), Some(target)) = (cell, buf.cell_mut((x, area.y + row))) {
+                        *target = cell.clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The width and height of a single seven-segment digit in cells
+const SEGMENT_WIDTH: u16 = 4;
+const SEGMENT_HEIGHT: u16 = 5;
+
+// The lit segments of every digit, from the top segment "a" in the lowest bit to the middle "g"
+const SEGMENTS: [u8; 10] = [
+    0b0111111, // 0
+    0b0000110, // 1
+    0b1011011, // 2
+    0b1001111, // 3
+    0b1100110, // 4
+    0b1101101, // 5
+    0b1111101, // 6
+    0b0000111, // 7
+    0b1111111, // 8
+    0b1101111, // 9
+];
+
+// The big timer digits, drawn with segments like on a classic LCD clock
+pub struct SevenSegment {
+    time: String, // The displayed time
+    color: Color, // The color of the segments
+}
+
+impl SevenSegment {
+    pub fn new(time: String, color: Color) -> Self {
+        Self { time, color }
+    }
+
+    // Get the width of the displayed time in cells
+    pub fn width(&self) -> u16 {
+        let glyphs: u16 = self.time.chars().map(glyph_width).sum();
+        let gaps = self.time.chars().count().saturating_sub(1) as u16;
+
+        glyphs + gaps
+    }
+}
+
+// Get the width of a single glyph in cells
+fn glyph_width(c: char) -> u16 {
+    if c.is_ascii_digit() {
+        SEGMENT_WIDTH
+    } else {
+        1
+    }
+}
+
+// Get the rows of a single glyph, where unknown characters are left blank
+fn glyph_rows(c: char) -> Vec<String> {
+    let Some(digit) = c.to_digit(10) else {
+        let (dot, point) = match c {
+            ':' => ("•", " "),
+            '.' => (" ", "•"),
+            _ => (" ", " "),
+        };
+
+        return vec![" ", dot, " ", dot, point]
+            .into_iter()
+            .map(String::from)
+            .collect();
+    };
+
+    let segments = SEGMENTS[digit as usize];
+    let lit = |segment: u8, on: &str, off: &str| {
+        if segments & (1 << segment) != 0 {
+            on.to_string()
+        } else {
+            off.to_string()
+        }
+    };
+
+    let horizontal = |segment| format!(" {} ", lit(segment, "━━", "  "));
+    let vertical = |left, right| format!("{}  {}", lit(left, "┃", " "), lit(right, "┃", " "));
+
+    vec![
+        horizontal(0),
+        vertical(5, 1),
+        horizontal(6),
+        vertical(4, 2),
+        horizontal(3),
+    ]
+}
+
+impl Widget for SevenSegment {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let style = Style::new().fg(self.color);
+
+        // Center the time in the area
+        let mut x = area.x + area.width.saturating_sub(self.width()) / 2;
+        let y = area.y + area.height.saturating_sub(SEGMENT_HEIGHT) / 2;
+
+        for c in self.time.chars() {
+            for (row, line) in glyph_rows(c).iter().enumerate() {
+                let row_y = y + row as u16;
+
+                if row_y < area.bottom() && x < area.right() {
+                    buf.set_stringn(x, row_y, line, (area.right() - x) as usize, style);
+                }
+            }
+
+            x += glyph_width(c) + 1;
+        }
+    }
+}
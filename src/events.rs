@@ -0,0 +1,241 @@
+use chrono::Local;
+use shlex::Shlex;
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use crate::{chat::ChatMessage, config::ExitCommandsOn};
+
+// An event emitted by the App, which is consumed by the registered sinks
+#[allow(dead_code)] // Not every payload is consumed by a sink yet
+#[derive(Clone, Debug)]
+pub enum AppEvent {
+    // The App has started, with the countdown duration if one is set
+    TimerStarted {
+        duration: Option<Duration>,
+    },
+    // Emitted every second with the current countdown state
+    Tick {
+        remaining: Option<Duration>,
+        percent: Option<u16>,
+    },
+    SongChanged(String),          // The current song has changed
+    MessageReceived(ChatMessage), // A new chat message was received
+    PhaseChanged(String),         // A new pomodoro phase has started, with its label
+    Finished,                     // The countdown has finished
+    Exited,                       // The App is exiting
+}
+
+// Something which consumes the events emitted by the App
+pub trait EventSink {
+    fn handle(&mut self, event: &AppEvent);
+}
+
+// Distributes emitted events to all registered sinks
+#[derive(Default)]
+pub struct EventBus {
+    sinks: Vec<Box<dyn EventSink>>, // All registered sinks
+}
+
+impl EventBus {
+    // Register a new sink which will receive all future events
+    pub fn register(&mut self, sink: impl EventSink + 'static) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    // Emit an event to all registered sinks
+    pub fn emit(&mut self, event: AppEvent) {
+        for sink in self.sinks.iter_mut() {
+            sink.handle(&event);
+        }
+    }
+}
+
+// Runs the configured commands when the App starts, changes pomodoro phases and exits
+pub struct CommandSink {
+    start_commands: Vec<String>,      // Commands run when starting brb
+    exit_commands: Vec<String>,       // Commands run when exiting brb
+    phase_commands: Vec<String>,      // Commands run when a pomodoro phase changes
+    start_script: Option<PathBuf>,    // Shell script run when starting brb
+    exit_script: Option<PathBuf>,     // Shell script run when exiting brb
+    exit_commands_on: ExitCommandsOn, // When the exit commands and script run
+    finished: bool,                   // Whether the countdown has finished
+}
+
+impl CommandSink {
+    pub fn new(
+        start_commands: Vec<String>,
+        exit_commands: Vec<String>,
+        phase_commands: Vec<String>,
+        start_script: Option<PathBuf>,
+        exit_script: Option<PathBuf>,
+        exit_commands_on: ExitCommandsOn,
+    ) -> Self {
+        Self {
+            start_commands,
+            exit_commands,
+            phase_commands,
+            start_script,
+            exit_script,
+            exit_commands_on,
+            finished: false,
+        }
+    }
+
+    // Run the exit commands and script
+    fn run_exit_commands(&self) {
+        execute_commands(&self.exit_commands);
+
+        if let Some(script) = &self.exit_script {
+            execute_script(script);
+        }
+    }
+}
+
+impl EventSink for CommandSink {
+    fn handle(&mut self, event: &AppEvent) {
+        match event {
+            AppEvent::TimerStarted { .. } => {
+                execute_commands(&self.start_commands);
+
+                if let Some(script) = &self.start_script {
+                    execute_script(script);
+                }
+            }
+            AppEvent::PhaseChanged(_) => execute_commands(&self.phase_commands),
+            AppEvent::Finished => {
+                self.finished = true;
+
+                if self.exit_commands_on == ExitCommandsOn::Finish {
+                    self.run_exit_commands();
+                }
+            }
+            AppEvent::Exited => match self.exit_commands_on {
+                ExitCommandsOn::Always => self.run_exit_commands(),
+                ExitCommandsOn::Quit if !self.finished => self.run_exit_commands(),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+// Writes the remaining time to a file every second, so it can be used by other programs
+pub struct FileSink {
+    path: PathBuf, // The file the remaining time is written to
+}
+
+impl FileSink {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    // Write the contents to the file, errors are ignored as they would mess with the TUI
+    fn write(&self, contents: &str) {
+        let _ = fs::write(&self.path, contents);
+    }
+}
+
+impl EventSink for FileSink {
+    fn handle(&mut self, event: &AppEvent) {
+        match event {
+            AppEvent::Tick {
+                remaining: Some(remaining),
+                ..
+            } => {
+                let secs = remaining.as_secs();
+                self.write(&format!("{:02}:{:02}", secs / 60, secs % 60));
+            }
+            AppEvent::Finished | AppEvent::Exited => self.write(""),
+            _ => {}
+        }
+    }
+}
+
+// Appends every received chat message to a log file
+pub struct ChatLogSink {
+    file: Option<BufWriter<File>>, // The opened log file, None if it couldn't be opened
+}
+
+impl ChatLogSink {
+    // Open the log file once, errors are ignored as they would mess with the TUI
+    pub fn new(path: PathBuf) -> Self {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .ok()
+            .map(BufWriter::new);
+
+        Self { file }
+    }
+}
+
+impl EventSink for ChatLogSink {
+    fn handle(&mut self, event: &AppEvent) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+
+        // Failed writes only lose the affected messages, logging continues with the next ones
+        match event {
+            AppEvent::MessageReceived(message) => {
+                let _ = writeln!(
+                    file,
+                    "[{}] {}: {}",
+                    Local::now().format("%Y-%m-%d %H:%M:%S"),
+                    message.sender,
+                    message.message
+                );
+            }
+            // Write the buffered messages to the file every second and when exiting
+            AppEvent::Tick { .. } | AppEvent::Exited => {
+                let _ = file.flush();
+            }
+            _ => {}
+        }
+    }
+}
+
+// Execute commands in the background
+pub fn execute_commands(commands: &[String]) {
+    for command in commands {
+        let parts = Shlex::new(command).collect::<Vec<String>>();
+        if let Some(first) = parts.first() {
+            let mut c = Command::new(first);
+            c.args(&parts[1..]);
+            spawn_silent(c);
+        }
+    }
+}
+
+// Execute a script file in the background using the user's shell
+pub fn execute_script(script: &Path) {
+    let mut c = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.arg("/C");
+        c
+    } else {
+        Command::new(env::var_os("SHELL").unwrap_or_else(|| "sh".into()))
+    };
+
+    c.arg(script);
+    spawn_silent(c);
+}
+
+// Spawn the command without any output
+fn spawn_silent(mut c: Command) {
+    // Don't output anything, as this would mess with the TUI
+    c.stdin(Stdio::null());
+    c.stdout(Stdio::null());
+    c.stderr(Stdio::null());
+
+    // Also ignore the Result in case the command is not found,
+    // as this would mess with the TUI
+    let _ = c.spawn();
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, Rgba};
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+use std::{
+    cell::{Ref, RefCell},
+    path::Path,
+};
+
+// The maximum size images are downscaled to when loading, so rendering stays fast
+const MAX_SIZE: u32 = 256;
+
+// An image which is rendered using half-block characters
+pub struct ImageDisplay {
+    image: DynamicImage,                                  // The loaded image
+    resized: RefCell<Option<((u16, u16), DynamicImage)>>, // The image scaled to the last area size
+}
+
+impl ImageDisplay {
+    // Load the image at the provided path
+    pub fn load(path: &Path) -> Result<Self> {
+        let image = image::open(path).context("Failed loading image")?;
+
+        // Downscale big images once, as they are never displayed bigger than the terminal
+        let image = if image.width() > MAX_SIZE || image.height() > MAX_SIZE {
+            image.resize(MAX_SIZE, MAX_SIZE, FilterType::Triangle)
+        } else {
+            image
+        };
+
+        Ok(Self {
+            image,
+            resized: RefCell::new(None),
+        })
+    }
+
+    // Get the image scaled to fit the area, which is only scaled again if the area size changed
+    fn resized(&self, width: u16, height: u16) -> Ref<'_, DynamicImage> {
+        let outdated =
+            !matches!(&*self.resized.borrow(), Some((size, _)) if *size == (width, height));
+
+        if outdated {
+            // Every cell displays two pixels on top of each other, so the image keeps its aspect
+            // ratio
+            let image = self
+                .image
+                .resize(width as u32, height as u32 * 2, FilterType::Nearest);
+            *self.resized.borrow_mut() = Some(((width, height), image));
+        }
+
+        // The image was scaled above if it wasn't cached yet
+        Ref::map(self.resized.borrow(), |resized| {
+            &resized.as_ref().unwrap().1
+        })
+    }
+}
+
+// Convert a pixel to a color, or None if it is mostly transparent
+fn pixel_color(pixel: Rgba<u8>) -> Option<Color> {
+    let [r, g, b, a] = pixel.0;
+    (a >= 128).then_some(Color::Rgb(r, g, b))
+}
+
+// Implement Widget for the ImageDisplay so it can be rendered
+impl Widget for &ImageDisplay {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let image = self.resized(area.width, area.height);
+        let (width, height) = image.dimensions();
+
+        // Center the image inside the area
+        let x_offset = area.x + (area.width - width as u16) / 2;
+        let y_offset = area.y + (area.height - height.div_ceil(2) as u16) / 2;
+
+        for y in (0..height).step_by(2) {
+            for x in 0..width {
+                let top = pixel_color(image.get_pixel(x, y));
+                let bottom = if y + 1 < height {
+                    pixel_color(image.get_pixel(x, y + 1))
+                } else {
+                    None
+                };
+
+                let cell = &mut buf[(x_offset + x as u16, y_offset + (y / 2) as u16)];
+
+                // Transparent pixels are left empty
+                match (top, bottom) {
+                    (Some(top), Some(bottom)) => {
+                        cell.set_symbol("▀").set_fg(top).set_bg(bottom);
+                    }
+                    (Some(top), None) => {
+                        cell.set_symbol("▀").set_fg(top);
+                    }
+                    (None, Some(bottom)) => {
+                        cell.set_symbol("▄").set_fg(bottom);
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+}
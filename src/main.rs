@@ -7,8 +7,26 @@ use anyhow::Result;
 mod args;
 mod chat;
 mod config;
+mod digits;
+mod events;
+#[cfg(feature = "image")]
+mod image;
+#[cfg(feature = "notifications")]
+mod notify;
+mod perf;
+mod pomodoro;
+mod progress;
+#[cfg(feature = "qr")]
+mod qr;
+#[cfg(feature = "scripting")]
+mod script;
+mod server;
 mod song;
+#[cfg(feature = "sound")]
+mod sound;
 mod state;
+#[cfg(feature = "youtube")]
+mod youtube;
 
 fn main() -> Result<()> {
     // Parse arguments
@@ -0,0 +1,91 @@
+use notify_rust::Notification;
+use std::{collections::HashSet, thread, time::Duration};
+
+use crate::{
+    config::Notifications,
+    events::{AppEvent, EventSink},
+};
+
+// Sends desktop notifications when the countdown crosses the configured milestones
+pub struct NotificationSink {
+    milestones: Notifications,        // The configured milestones
+    fired: HashSet<u64>,              // Remaining seconds milestones which were already sent
+    halfway_fired: bool,              // Whether the halfway notification was already sent
+    last_remaining: Option<Duration>, // The remaining time of the previous tick
+}
+
+impl NotificationSink {
+    pub fn new(milestones: Notifications) -> Self {
+        Self {
+            milestones,
+            fired: HashSet::new(),
+            halfway_fired: false,
+            last_remaining: None,
+        }
+    }
+
+    // Check the remaining seconds milestones which were crossed since the last tick
+    fn check_remaining(&mut self, remaining: Duration) {
+        let Some(last_remaining) = self.last_remaining.replace(remaining) else {
+            return;
+        };
+
+        for &secs in self.milestones.remaining_secs.iter() {
+            let milestone = Duration::from_secs(secs);
+
+            if last_remaining > milestone && remaining <= milestone && self.fired.insert(secs) {
+                send(&format!("{} remaining", format_milestone(secs)));
+            }
+        }
+    }
+}
+
+impl EventSink for NotificationSink {
+    fn handle(&mut self, event: &AppEvent) {
+        match event {
+            AppEvent::Tick {
+                remaining: Some(remaining),
+                percent,
+            } => {
+                self.check_remaining(*remaining);
+
+                if self.milestones.halfway
+                    && !self.halfway_fired
+                    && percent.is_some_and(|p| p >= 50)
+                {
+                    self.halfway_fired = true;
+                    send("Halfway there");
+                }
+            }
+            // Every pomodoro phase has its own milestones
+            AppEvent::PhaseChanged(_) => {
+                self.fired.clear();
+                self.halfway_fired = false;
+                self.last_remaining = None;
+            }
+            AppEvent::Finished if self.milestones.finished => send("Time is up"),
+            _ => {}
+        }
+    }
+}
+
+// Format the milestone like "1 minute" or "30 seconds"
+fn format_milestone(secs: u64) -> String {
+    let (amount, unit) = if secs >= 60 && secs.is_multiple_of(60) {
+        (secs / 60, "minute")
+    } else {
+        (secs, "second")
+    };
+
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {}{}", amount, unit, plural)
+}
+
+// Send the notification in the background, errors are ignored if notifications are unsupported
+fn send(body: &str) {
+    let body = body.to_string();
+
+    thread::spawn(move || {
+        let _ = Notification::new().summary("brb").body(&body).show();
+    });
+}
@@ -0,0 +1,68 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+// Statistics about the render loop, which are printed on exit in perf mode
+pub struct PerfStats {
+    started: Instant,     // When collecting the statistics started
+    frames: u64,          // How many frames were drawn
+    draw_total: Duration, // The total time spent drawing frames
+    draw_max: Duration,   // The longest time spent drawing a single frame
+    messages: u64,        // How many chat messages were processed
+    song_polls: u64,      // How often the current song was polled
+}
+
+impl Default for PerfStats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            frames: 0,
+            draw_total: Duration::ZERO,
+            draw_max: Duration::ZERO,
+            messages: 0,
+            song_polls: 0,
+        }
+    }
+}
+
+impl PerfStats {
+    // Record how long drawing a frame took
+    pub fn record_draw(&mut self, duration: Duration) {
+        self.frames += 1;
+        self.draw_total += duration;
+        self.draw_max = self.draw_max.max(duration);
+    }
+
+    // Record how many chat messages were processed
+    pub fn record_messages(&mut self, count: usize) {
+        self.messages += count as u64;
+    }
+
+    // Record that the current song was polled
+    pub fn record_song_poll(&mut self) {
+        self.song_polls += 1;
+    }
+}
+
+impl fmt::Display for PerfStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let runtime = self.started.elapsed();
+        let draw_avg = self
+            .draw_total
+            .checked_div(self.frames as u32)
+            .unwrap_or_default();
+
+        writeln!(f, "Runtime:        {:.1?}", runtime)?;
+        writeln!(
+            f,
+            "Frames:         {} ({:.1} per second)",
+            self.frames,
+            self.frames as f64 / runtime.as_secs_f64()
+        )?;
+        writeln!(f, "Average draw:   {:.2?}", draw_avg)?;
+        writeln!(f, "Maximum draw:   {:.2?}", self.draw_max)?;
+        writeln!(f, "Chat messages:  {}", self.messages)?;
+        write!(f, "Song polls:     {}", self.song_polls)
+    }
+}
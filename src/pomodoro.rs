@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+// The phases a pomodoro cycle consists of
+#[derive(Clone, Copy, PartialEq)]
+pub enum PomodoroPhase {
+    Work,
+    Break,
+}
+
+// Repeating work and break phases, the break after the last work phase is skipped
+pub struct Pomodoro {
+    work: Duration,       // Duration of a work phase
+    rest: Duration,       // Duration of a break phase
+    cycles: u32,          // How many work phases there are
+    cycle: u32,           // The currently active cycle, starting at 1
+    phase: PomodoroPhase, // The currently active phase
+}
+
+impl Pomodoro {
+    pub fn new(work: Duration, rest: Duration, cycles: u32) -> Self {
+        Self {
+            work,
+            rest,
+            cycles: cycles.max(1),
+            cycle: 1,
+            phase: PomodoroPhase::Work,
+        }
+    }
+
+    // Get the duration of the currently active phase
+    pub fn get_duration(&self) -> Duration {
+        match self.phase {
+            PomodoroPhase::Work => self.work,
+            PomodoroPhase::Break => self.rest,
+        }
+    }
+
+    // Advance to the next phase, returns false if there are no phases left
+    pub fn advance(&mut self) -> bool {
+        match self.phase {
+            PomodoroPhase::Work if self.cycle < self.cycles => {
+                self.phase = PomodoroPhase::Break;
+                true
+            }
+            PomodoroPhase::Work => false,
+            PomodoroPhase::Break => {
+                self.phase = PomodoroPhase::Work;
+                self.cycle += 1;
+                true
+            }
+        }
+    }
+
+    // Get the label of the currently active phase, like "Work 1/4"
+    pub fn label(&self) -> String {
+        let phase = match self.phase {
+            PomodoroPhase::Work => "Work",
+            PomodoroPhase::Break => "Break",
+        };
+
+        format!("{} {}/{}", phase, self.cycle, self.cycles)
+    }
+}
@@ -0,0 +1,271 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Modifier, Style},
+    widgets::Widget,
+};
+
+use crate::config::ColorDepth;
+
+// The 16 basic ANSI colors and their common RGB values
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 49, 49)),
+    (Color::Green, (13, 188, 121)),
+    (Color::Yellow, (229, 229, 16)),
+    (Color::Blue, (36, 114, 200)),
+    (Color::Magenta, (188, 63, 188)),
+    (Color::Cyan, (17, 168, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (102, 102, 102)),
+    (Color::LightRed, (241, 76, 76)),
+    (Color::LightGreen, (35, 209, 139)),
+    (Color::LightYellow, (245, 245, 67)),
+    (Color::LightBlue, (59, 142, 234)),
+    (Color::LightMagenta, (214, 112, 214)),
+    (Color::LightCyan, (41, 184, 219)),
+    (Color::White, (255, 255, 255)),
+];
+
+// The RGB values of the six levels of every channel in the 256 color cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// A thin ring around an area, which fills up clockwise starting at the top center
+pub struct ProgressRing {
+    percent: u16, // How much of the ring is filled in percent
+    color: Color, // The color of the filled part
+}
+
+impl ProgressRing {
+    pub fn new(percent: u16, color: Color) -> Self {
+        Self { percent, color }
+    }
+
+    // Get the positions of the ring in clockwise order, starting at the top center
+    fn positions(area: Rect) -> Vec<Position> {
+        let (left, top) = (area.left(), area.top());
+        let (right, bottom) = (area.right() - 1, area.bottom() - 1);
+
+        let mut positions = Vec::new();
+        positions.extend((left..=right).map(|x| Position::new(x, top)));
+        positions.extend((top + 1..=bottom).map(|y| Position::new(right, y)));
+        positions.extend((left..right).rev().map(|x| Position::new(x, bottom)));
+        positions.extend((top + 1..bottom).rev().map(|y| Position::new(left, y)));
+
+        // Start at the top center instead of the top left corner
+        let center = (area.width / 2) as usize;
+        positions.rotate_left(center);
+
+        positions
+    }
+
+    // Get the symbol for a position on the ring, thick if it is filled
+    fn symbol(area: Rect, position: Position, filled: bool) -> &'static str {
+        let (left, top) = (area.left(), area.top());
+        let (right, bottom) = (area.right() - 1, area.bottom() - 1);
+
+        match (position.x, position.y, filled) {
+            (x, y, true) if x == left && y == top => "┏",
+            (x, y, true) if x == right && y == top => "┓",
+            (x, y, true) if x == left && y == bottom => "┗",
+            (x, y, true) if x == right && y == bottom => "┛",
+            (_, y, true) if y == top || y == bottom => "━",
+            (_, _, true) => "┃",
+            (x, y, false) if x == left && y == top => "╭",
+            (x, y, false) if x == right && y == top => "╮",
+            (x, y, false) if x == left && y == bottom => "╰",
+            (x, y, false) if x == right && y == bottom => "╯",
+            (_, y, false) if y == top || y == bottom => "─",
+            (_, _, false) => "│",
+        }
+    }
+}
+
+// Implement Widget for the ProgressRing so it can be rendered
+impl Widget for ProgressRing {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // A ring needs at least two rows and columns
+        if area.width < 2 || area.height < 2 {
+            return;
+        }
+
+        let positions = Self::positions(area);
+        let filled = positions.len() * self.percent.min(100) as usize / 100;
+
+        for (i, position) in positions.into_iter().enumerate() {
+            let is_filled = i < filled;
+
+            // The unfilled part of the ring is dimmed
+            let style = if is_filled {
+                Style::new().fg(self.color)
+            } else {
+                Style::new().fg(Color::DarkGray)
+            };
+
+            buf[position]
+                .set_symbol(Self::symbol(area, position, is_filled))
+                .set_style(style);
+        }
+    }
+}
+
+// A compact timer, displaying the time centered on a bar which fills up with a color gradient
+pub struct TimerBar {
+    time: String, // The formatted remaining time
+    percent: u16, // How much of the bar is filled in percent
+    color: Color, // The color the gradient fades into
+}
+
+impl TimerBar {
+    pub fn new(time: String, percent: u16, color: Color) -> Self {
+        Self {
+            time,
+            percent,
+            color,
+        }
+    }
+
+    // Get the gradient color at a position between 0.0 (darkest) and 1.0 (the full color)
+    fn gradient(&self, position: f64) -> Color {
+        let (r, g, b) = to_rgb(self.color);
+
+        // The gradient starts at a third of the colors brightness
+        let factor = 1.0 / 3.0 + position * 2.0 / 3.0;
+        let scale = |c: u8| (c as f64 * factor).round() as u8;
+
+        Color::Rgb(scale(r), scale(g), scale(b))
+    }
+}
+
+// Implement Widget for the TimerBar so it can be rendered
+impl Widget for TimerBar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        // The bar is a single line in the vertical center of the area
+        let y = area.y + area.height / 2;
+        let filled = area.width as usize * self.percent.min(100) as usize / 100;
+
+        // Paint the filled part with the gradient
+        for i in 0..filled {
+            let position = i as f64 / (area.width.max(2) - 1) as f64;
+            buf[(area.x + i as u16, y)].set_bg(self.gradient(position));
+        }
+
+        // Overlay the time in the center, readable on both the filled and unfilled part
+        let time_width = self.time.chars().count() as u16;
+        let x_offset = area.x + area.width.saturating_sub(time_width) / 2;
+
+        for (i, c) in self.time.chars().enumerate().take(area.width as usize) {
+            let x = x_offset + i as u16;
+            let fg = if ((x - area.x) as usize) < filled {
+                Color::Black
+            } else {
+                self.color
+            };
+
+            buf[(x, y)]
+                .set_char(c)
+                .set_style(Style::new().fg(fg).add_modifier(Modifier::BOLD));
+        }
+    }
+}
+
+// Scale the brightness of a color, a factor of 1.0 keeps the color unchanged
+pub fn scale_brightness(color: Color, factor: f64) -> Color {
+    let (r, g, b) = to_rgb(color);
+    let scale = |c: u8| (c as f64 * factor).round().clamp(0.0, 255.0) as u8;
+
+    Color::Rgb(scale(r), scale(g), scale(b))
+}
+
+// Reduce a RGB color to the nearest color the terminal supports, other colors are kept as is
+pub fn reduce_color(color: Color, depth: ColorDepth) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        // Indexed colors are only reduced if the terminal lacks the 256 color palette
+        Color::Indexed(index) if depth == ColorDepth::Ansi16 && index >= 16 => {
+            indexed_to_rgb(index)
+        }
+        _ => return color,
+    };
+
+    match depth {
+        ColorDepth::Truecolor => color,
+        ColorDepth::Ansi256 => Color::Indexed(nearest_indexed(r, g, b)),
+        ColorDepth::Ansi16 => ANSI_COLORS
+            .iter()
+            .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+            .map_or(color, |(ansi, _)| *ansi),
+    }
+}
+
+// Get the index of the nearest color in the 256 color palette, either in the color cube or
+// the grayscale ramp
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| match c {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (c - 35) / 40,
+    };
+
+    let (lr, lg, lb) = (level(r), level(g), level(b));
+    let cube = (
+        CUBE_LEVELS[lr as usize],
+        CUBE_LEVELS[lg as usize],
+        CUBE_LEVELS[lb as usize],
+    );
+
+    // The grayscale ramp goes from 8 to 238 in 24 steps
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + gray_step * 10;
+
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube) {
+        232 + gray_step
+    } else {
+        16 + 36 * lr + 6 * lg + lb
+    }
+}
+
+// Get the squared distance between two RGB colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let diff = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    diff(a.0, b.0) + diff(a.1, b.1) + diff(a.2, b.2)
+}
+
+// Get the RGB values of a color, named colors are mapped to their common RGB values
+pub fn to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => indexed_to_rgb(index),
+        _ => ANSI_COLORS
+            .iter()
+            .find(|(ansi, _)| *ansi == color)
+            .map_or((229, 229, 229), |(_, rgb)| *rgb),
+    }
+}
+
+// Get the RGB values of a color in the 256 color palette
+fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        // The first 16 colors are the basic ANSI colors
+        0..=15 => ANSI_COLORS[index as usize].1,
+        // Followed by the 6x6x6 color cube
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        // And the grayscale ramp from 8 to 238
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
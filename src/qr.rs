@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use qrcode::{Color, QrCode};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+// Amount of light modules surrounding the code, so it can be scanned
+const QUIET_ZONE: usize = 2;
+
+// A QR code which is rendered using half-block characters
+pub struct QrDisplay {
+    width: usize,       // The width of the code in modules, including the quiet zone
+    modules: Vec<bool>, // Whether each module is light, row by row
+}
+
+impl QrDisplay {
+    // Create a QR code encoding the provided data
+    pub fn new(data: &str) -> Result<Self> {
+        let code = QrCode::new(data).context("Failed generating QR code")?;
+        let code_width = code.width();
+        let colors = code.to_colors();
+
+        let width = code_width + 2 * QUIET_ZONE;
+        let mut modules = vec![true; width * width];
+
+        // Copy the code into the center, leaving the quiet zone light
+        for (i, color) in colors.iter().enumerate() {
+            let (x, y) = (i % code_width + QUIET_ZONE, i / code_width + QUIET_ZONE);
+            modules[y * width + x] = *color == Color::Light;
+        }
+
+        Ok(Self { width, modules })
+    }
+
+    // Whether the module at the provided position is light
+    fn is_light(&self, x: usize, y: usize) -> bool {
+        y < self.width && self.modules[y * self.width + x]
+    }
+}
+
+// Implement Widget for the QrDisplay so it can be rendered
+impl Widget for &QrDisplay {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Every cell displays two modules on top of each other
+        let width = self.width as u16;
+        let height = self.width.div_ceil(2) as u16;
+
+        // Don't render anything if the code doesn't fit, as it couldn't be scanned anyway
+        if width > area.width || height > area.height {
+            return;
+        }
+
+        // Center the code inside the area
+        let x_offset = area.x + (area.width - width) / 2;
+        let y_offset = area.y + (area.height - height) / 2;
+
+        for row in 0..height {
+            for col in 0..width {
+                let (x, y) = (col as usize, row as usize * 2);
+
+                // Light modules are drawn, dark modules are left empty
+                let symbol = match (self.is_light(x, y), self.is_light(x, y + 1)) {
+                    (true, true) => "█",
+                    (true, false) => "▀",
+                    (false, true) => "▄",
+                    (false, false) => " ",
+                };
+
+                buf[(x_offset + col, y_offset + row)].set_symbol(symbol);
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::{anyhow, Context, Result};
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+use std::{fs, path::Path, time::Duration};
+
+use crate::{args, config::TomlColor};
+
+// The state which is passed to the script on every tick
+pub struct TickState {
+    pub remaining: Option<Duration>, // The remaining time of the countdown
+    pub percent: Option<u16>,        // How much of the countdown has elapsed in percent
+    pub messages: usize,             // How many chat messages were received
+}
+
+// Values returned by the script which override the current configuration
+#[derive(Default)]
+pub struct TickOverrides {
+    pub text: Option<String>,     // A new text to display
+    pub color: Option<TomlColor>, // A new accent color
+}
+
+// A user provided Rhai script which can define an "on_tick" function
+pub struct Script {
+    engine: Engine,        // The engine used to run the script
+    ast: AST,              // The compiled script
+    scope: Scope<'static>, // Variables persisted between ticks
+}
+
+impl Script {
+    // Load and compile the script at the provided path
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path).context("Failed reading script file")?;
+
+        let engine = Engine::new();
+        let ast = engine
+            .compile(source)
+            .map_err(|e| anyhow!("{}", e))
+            .context("Failed compiling script")?;
+
+        let mut scope = Scope::new();
+
+        // Run the top level statements once, so the script can initialize its variables
+        engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| anyhow!("{}", e))
+            .context("Failed running script")?;
+
+        Ok(Self { engine, ast, scope })
+    }
+
+    // Call the "on_tick" function of the script, if it is defined
+    pub fn on_tick(&mut self, state: TickState) -> TickOverrides {
+        // Build the state map which is passed to the script
+        let mut map = Map::new();
+        map.insert(
+            "remaining".into(),
+            state
+                .remaining
+                .map(|r| Dynamic::from_int(r.as_secs() as i64))
+                .unwrap_or(Dynamic::UNIT),
+        );
+        map.insert(
+            "percent".into(),
+            state
+                .percent
+                .map(|p| Dynamic::from_int(p as i64))
+                .unwrap_or(Dynamic::UNIT),
+        );
+        map.insert("messages".into(), Dynamic::from_int(state.messages as i64));
+
+        // Don't evaluate the top level statements again, so variables persist between ticks
+        let options = CallFnOptions::new().eval_ast(false);
+
+        // Errors are ignored, as printing them would mess with the TUI
+        let Ok(result) = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut self.scope,
+            &self.ast,
+            "on_tick",
+            (map,),
+        ) else {
+            return TickOverrides::default();
+        };
+
+        // The script may return a map containing overrides
+        let Some(result) = result.try_cast::<Map>() else {
+            return TickOverrides::default();
+        };
+
+        let text = result
+            .get("text")
+            .and_then(|t| t.clone().into_string().ok());
+
+        let color = result
+            .get("color")
+            .and_then(|c| c.clone().into_string().ok())
+            .and_then(|c| args::color_arg_parser(&c).ok());
+
+        TickOverrides { text, color }
+    }
+}
@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+// The largest request which is read, only the request line is needed
+const MAX_REQUEST_SIZE: usize = 4096;
+
+// A snapshot of the countdown, which is updated by the App and read by the status server
+#[derive(Clone, Copy, Default)]
+pub struct TimerStatus {
+    pub remaining: Option<Duration>, // The remaining time of the countdown
+    pub total: Option<Duration>,     // The original duration of the countdown
+    pub percentage: Option<u16>,     // How much of the time has elapsed in percent
+    pub paused: bool,                // Whether the countdown is paused
+}
+
+impl TimerStatus {
+    // Serialize the status as JSON, values which aren't known are null
+    fn to_json(self) -> String {
+        let seconds = |duration: Option<Duration>| {
+            duration.map_or("null".to_string(), |d| d.as_secs().to_string())
+        };
+
+        format!(
+            "{{\"remaining_seconds\":{},\"total_seconds\":{},\"percentage\":{},\"paused\":{}}}",
+            seconds(self.remaining),
+            seconds(self.total),
+            self.percentage
+                .map_or("null".to_string(), |p| p.to_string()),
+            self.paused
+        )
+    }
+}
+
+// The status which is shared between the App and the status server
+pub type SharedStatus = Arc<Mutex<TimerStatus>>;
+
+// Bind the status server to the provided address, so errors are reported before starting
+pub async fn bind(address: SocketAddr) -> Result<TcpListener> {
+    TcpListener::bind(address)
+        .await
+        .with_context(|| format!("Failed starting the status server on {}", address))
+}
+
+// Answer requests for the status until the runtime shuts down
+pub async fn serve(listener: TcpListener, status: SharedStatus) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+
+        tokio::spawn(handle_connection(stream, status.clone()));
+    }
+}
+
+// Answer a single request, the server is read-only so only "GET /status" is supported
+async fn handle_connection(mut stream: TcpStream, status: SharedStatus) {
+    let mut buf = vec![0; MAX_REQUEST_SIZE];
+    let Ok(length) = stream.read(&mut buf).await else {
+        return;
+    };
+
+    let request = String::from_utf8_lossy(&buf[..length]);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+
+    let response = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/status")) => {
+            // Copy the status, so the lock isn't held while writing the response
+            let snapshot = *status.lock().unwrap();
+            response("200 OK", &snapshot.to_json())
+        }
+        (Some("GET"), _) => response("404 Not Found", "{\"error\":\"not found\"}"),
+        _ => response(
+            "405 Method Not Allowed",
+            "{\"error\":\"method not allowed\"}",
+        ),
+    };
+
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+// Build an HTTP response with a JSON body, which browser sources on other origins may read
+fn response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
@@ -1,65 +1,419 @@
+use anyhow::{bail, Context, Result};
 use std::{
     io::Read,
-    process::{Child, Command, Stdio},
+    process::{Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
 };
 
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    widgets::{Paragraph, Widget, Wrap},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::{Gauge, Paragraph, Widget, Wrap},
 };
+use shlex::Shlex;
+
+// How long to wait before running the song commands again
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// How long a song command may run before it is killed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The playerctl formats which output the song, and its position and length in microseconds
+const PLAYERCTL_SONG_FORMAT: &str = "{{ artist }} - {{ title }}";
+const PLAYERCTL_PROGRESS_FORMAT: &str = "{{ position }} {{ mpris:length }}";
 
 pub struct SongDisplay {
     current_song: String,
-    child: Option<Child>,
+    songs: Option<mpsc::Receiver<String>>, // Receives the output of the song command, until stopped
+    displayed_at: Option<Instant>,         // When the current song was first displayed
+    min_display: Duration,                 // Minimum time a song is displayed before being replaced
+    next_songs: Option<mpsc::Receiver<Option<String>>>, // Receives the next song, if it is displayed
+    next_song: Option<String>,                          // The next song, None if it is unknown
+    progress_updates: Option<mpsc::Receiver<Option<SongProgress>>>, // Receives the song progress if shown
+    progress: Option<SongProgress>, // How far into the current song, None if it is unknown
+}
+
+// How far into the current song the player is
+#[derive(Clone, Copy)]
+struct SongProgress {
+    position: Duration, // The position in the current song
+    length: Duration,   // The length of the current song
 }
 
 impl SongDisplay {
-    pub fn new() -> Self {
+    pub fn new(
+        min_display: Duration,
+        strip_output: bool,
+        next_command: Option<String>,
+        command: String,
+        player: Option<String>,
+        args: Vec<String>,
+        progress_command: Option<String>,
+    ) -> Self {
+        // Same as the song, the progress is queried from the selected player instead of the command
+        let progress_updates = progress_command.map(|command| {
+            let command: Vec<String> = Shlex::new(&command).collect();
+            let player = player.clone();
+
+            spawn_poller(move || poll_progress(&command, player.as_deref()))
+        });
+
+        let song_command = SongCommand {
+            command: Shlex::new(&command).collect(),
+            player,
+            args,
+        };
+
+        // The commands run on separate threads, so a slow command doesn't block rendering
+        let songs = spawn_poller(move || match song_command.run() {
+            Ok((song, _)) if strip_output => strip_formatting(&song),
+            Ok((song, _)) => song,
+            Err(e) => e.to_string(),
+        });
+
+        let next_songs = next_command
+            .map(|c| Shlex::new(&c).collect::<Vec<String>>())
+            .filter(|c| !c.is_empty())
+            .map(|next_command| spawn_poller(move || poll_next_song(&next_command, strip_output)));
+
         Self {
             current_song: "Getting current song ...".to_string(),
-            child: None,
+            songs: Some(songs),
+            displayed_at: None,
+            min_display,
+            next_songs,
+            next_song: None,
+            progress_updates,
+            progress: None,
+        }
+    }
+
+    // Update the displayed song, unless the current one hasn't been displayed for long enough.
+    // Returns the new song if it was changed.
+    fn set_song(&mut self, song: String) -> Option<String> {
+        if song == self.current_song {
+            return None;
+        }
+
+        if let Some(displayed_at) = self.displayed_at {
+            if displayed_at.elapsed() < self.min_display {
+                return None;
+            }
+        }
+
+        self.current_song = song.clone();
+        self.displayed_at = Some(Instant::now());
+
+        Some(song)
+    }
+
+    // Get the height the song display needs in rows
+    pub fn height(&self) -> u16 {
+        if self.progress_updates.is_some() {
+            3
+        } else {
+            2
+        }
+    }
+
+    // Take the latest song without blocking, returning the new song if it has changed
+    pub fn poll_song(&mut self) -> Option<String> {
+        if let Some(progress) = self.progress_updates.as_ref().and_then(latest) {
+            self.progress = progress;
+        }
+
+        if let Some(next_song) = self.next_songs.as_ref().and_then(latest) {
+            self.next_song = next_song;
         }
+
+        let song = self.songs.as_ref().and_then(latest)?;
+        self.set_song(song)
+    }
+
+    // Stop polling, the threads finish once their running command is done
+    pub fn stop(&mut self) {
+        self.songs = None;
+        self.next_songs = None;
+        self.progress_updates = None;
     }
+}
 
-    pub fn poll_song(&mut self) {
-        if let Some(mut child) = self.child.take() {
-            if let Some(mut stdout) = child.stdout.take() {
-                let mut buf = String::new();
+// The command which outputs the current song
+struct SongCommand {
+    command: Vec<String>,   // Command which outputs the current song
+    player: Option<String>, // The media player which is queried instead of the command
+    args: Vec<String>,      // Extra arguments appended to the song command
+}
 
-                self.current_song = match stdout.read_to_string(&mut buf) {
-                    Ok(_) => buf,
-                    Err(_) => "Failed reading output".to_string(),
+impl SongCommand {
+    // Run the command, returning its output and whether it succeeded
+    fn run(&self) -> Result<(String, bool)> {
+        let (mut command, error) = match &self.player {
+            // Only query the selected player, using the MPRIS interface through playerctl
+            Some(player) => (
+                playerctl_metadata(player, PLAYERCTL_SONG_FORMAT),
+                "Failed running playerctl".to_string(),
+            ),
+            None => {
+                let Some((program, args)) = self.command.split_first() else {
+                    bail!("No song command configured");
                 };
 
-                self.child = Some(child);
-                return;
+                let mut command = Command::new(program);
+                command.args(args);
+
+                (command, format!("Failed running \"{}\"", program))
             }
+        };
+
+        command.args(&self.args);
+
+        run_with_timeout(command, error)
+    }
+}
+
+// Get the next song, which is hidden if the command fails
+fn poll_next_song(next_command: &[String], strip_output: bool) -> Option<String> {
+    let (program, args) = next_command.split_first()?;
+
+    let mut command = Command::new(program);
+    command.args(args);
+
+    let (output, _) = run_with_timeout(command, "Failed running the next song command".to_string())
+        .ok()
+        .filter(|(_, success)| *success)?;
+
+    let song = if strip_output {
+        strip_formatting(&output)
+    } else {
+        output
+    };
+
+    Some(song.trim().to_string()).filter(|song| !song.is_empty())
+}
+
+// Get the position and length of the current song, which is unknown if the command fails
+fn poll_progress(command: &[String], player: Option<&str>) -> Option<SongProgress> {
+    let command = match player {
+        Some(player) => playerctl_metadata(player, PLAYERCTL_PROGRESS_FORMAT),
+        None => {
+            let (program, args) = command.split_first()?;
+
+            let mut command = Command::new(program);
+            command.args(args);
+            command
         }
+    };
+
+    let (output, _) = run_with_timeout(
+        command,
+        "Failed running the song progress command".to_string(),
+    )
+    .ok()
+    .filter(|(_, success)| *success)?;
+
+    // Both values are in microseconds, like in the MPRIS interface
+    let mut values = output.split_whitespace().map(|value| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite() && *v >= 0.0)
+    });
+
+    let position = Duration::from_micros(values.next()?? as u64);
+    let length = Duration::from_micros(values.next()?? as u64);
+
+    // Nothing is displayed for streams and other songs without a length
+    (!length.is_zero()).then_some(SongProgress { position, length })
+}
 
-        match Command::new("sc")
-            .arg("current")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .stdin(Stdio::null())
-            .spawn()
-        {
-            Ok(child) => self.child = Some(child),
-            Err(_) => self.current_song = "Failed running spotic".to_string(),
+// Build a playerctl command, which outputs the metadata of a player in the provided format
+fn playerctl_metadata(player: &str, format: &str) -> Command {
+    let mut command = Command::new("playerctl");
+    command
+        .arg("--player")
+        .arg(player)
+        .args(["metadata", "--format", format]);
+    command
+}
+
+// Format a position in a song like "3:07"
+fn format_position(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+// Run a function on a separate thread until the receiver is dropped, sending back every result
+fn spawn_poller<T: Send + 'static>(poll: impl Fn() -> T + Send + 'static) -> mpsc::Receiver<T> {
+    let (tx, rx) = mpsc::sync_channel(1);
+
+    thread::spawn(move || loop {
+        if tx.send(poll()).is_err() {
+            return;
         }
+
+        thread::sleep(POLL_INTERVAL);
+    });
+
+    rx
+}
+
+// Take the latest result of a poller without blocking, skipping older ones
+fn latest<T>(rx: &mpsc::Receiver<T>) -> Option<T> {
+    rx.try_iter().last()
+}
+
+// Run a command, returning its output and whether it succeeded.
+// The command is killed if it doesn't finish within the timeout.
+fn run_with_timeout(mut command: Command, error: String) -> Result<(String, bool)> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .stdin(Stdio::null())
+        .spawn()
+        .context(error)?;
+
+    let mut stdout = child.stdout.take().context("Failed reading output")?;
+
+    // Read the output on another thread, so waiting for it can time out
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = tx.send(stdout.read_to_string(&mut buf).map(|_| buf));
+    });
+
+    let Ok(output) = rx.recv_timeout(COMMAND_TIMEOUT) else {
+        let _ = child.kill();
+        let _ = child.wait();
+        bail!(
+            "The song command timed out after {} seconds",
+            COMMAND_TIMEOUT.as_secs()
+        );
+    };
+
+    let output = output.context("Failed reading output")?;
+    let success = child.wait().is_ok_and(|status| status.success());
+
+    Ok((output, success))
+}
+
+// List the names of the available media players, which can be selected as the song player
+pub fn list_players() -> Result<Vec<String>> {
+    let output = Command::new("playerctl")
+        .arg("--list-all")
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed running playerctl, make sure it is installed")?;
+
+    if !output.status.success() {
+        bail!(
+            "Failed listing media players: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
 
-    pub fn stop(&mut self) {
-        if let Some(mut c) = self.child.take() {
-            let _ = c.kill();
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+// Remove ANSI escape sequences and control characters, which would corrupt the rendering
+fn strip_formatting(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI sequences like colors end with a character in the range '@' to '~'
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC sequences like titles end with BEL or ESC '\'
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Other escape sequences consist of a single character
+                _ => {}
+            },
+            '\n' => result.push(c),
+            '\t' => result.push(' '),
+            c if c.is_control() => {}
+            c => result.push(c),
         }
     }
+
+    result
 }
 
 impl Widget for &SongDisplay {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let song = Paragraph::new(self.current_song.clone()).wrap(Wrap { trim: true });
+        // If the next song is known, display it dimmed below the emphasized current song
+        let text = match &self.next_song {
+            Some(next_song) => Text::from(vec![
+                Line::styled(
+                    format!("Now: {}", self.current_song.trim()),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Line::styled(
+                    format!("Next: {}", next_song),
+                    Style::new().add_modifier(Modifier::DIM),
+                ),
+            ]),
+            None => Text::from(self.current_song.clone()),
+        };
+
+        let song = Paragraph::new(text).wrap(Wrap { trim: true });
+
+        // If the progress is displayed, it takes the row below the song
+        let (Some(progress), true) = (
+            self.progress_updates.as_ref().and(self.progress),
+            area.height > 1,
+        ) else {
+            song.render(area, buf);
+            return;
+        };
+
+        let song_area = Rect {
+            height: area.height.min(self.height()) - 1,
+            ..area
+        };
+        let progress_area = Rect {
+            y: song_area.bottom(),
+            height: 1,
+            ..area
+        };
+
+        song.render(song_area, buf);
+
+        let ratio = progress.position.as_secs_f64() / progress.length.as_secs_f64();
 
-        song.render(area, buf);
+        Gauge::default()
+            .use_unicode(true)
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(format!(
+                "{} / {}",
+                format_position(progress.position),
+                format_position(progress.length)
+            ))
+            .render(progress_area, buf);
     }
 }
@@ -0,0 +1,20 @@
+use rodio::{Decoder, OutputStream, Sink};
+use std::{fs::File, io::BufReader, path::PathBuf, thread};
+
+// Play the sound file at the provided path once in the background
+pub fn play(path: PathBuf) {
+    thread::spawn(move || {
+        // Errors are ignored, as a missing file or audio device shouldn't stop brb
+        let _ = (|| -> anyhow::Result<()> {
+            // The output stream has to be kept alive while the sound is playing
+            let (_stream, handle) = OutputStream::try_default()?;
+            let sink = Sink::try_new(&handle)?;
+
+            let file = File::open(path)?;
+            sink.append(Decoder::new(BufReader::new(file))?);
+            sink.sleep_until_end();
+
+            Ok(())
+        })();
+    });
+}
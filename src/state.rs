@@ -1,32 +1,110 @@
-use ratatui::widgets::{Gauge, Padding};
-use anyhow::{Context, Result};
+use ratatui::widgets::{BorderType, Clear, Gauge, Padding, Paragraph};
+use anyhow::{bail, Context, Result};
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent},
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Style, Stylize},
+    crossterm::{
+        event::{self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEvent},
+        execute,
+    },
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Modifier, Style, Stylize},
     text::Line,
     widgets::{Block, Borders, Widget},
-    DefaultTerminal, Frame,
+    Frame, Terminal,
 };
-use shlex::Shlex;
-use std::process::{Command, Stdio};
-use std::time::{Duration, Instant};
+use clap::ArgMatches;
+use std::collections::HashSet;
+use std::fs;
+use std::net::SocketAddr;
+use std::io::{stdout, Write};
+use std::thread;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use chrono::Local;
 use tokio::runtime::{Builder, Runtime};
 use tui_big_text::{BigText, PixelSize};
 
-use crate::chat::TwitchChat;
-use crate::config::Config;
+use crate::args;
+use crate::chat::{ChatChannel, ChatDisplay, ChatMessage};
+use crate::digits::{RollingDigits, SevenSegment};
+use crate::events::{self, AppEvent, ChatLogSink, CommandSink, EventBus, FileSink};
+use crate::config::{BigTextSize, ClockSource, Preset, Config, LayoutElement, ProgressStyle, SongPosition, TimerFont, TimerRound, TimerStyle, VerticalAlign};
+use crate::perf::PerfStats;
+use crate::pomodoro::Pomodoro;
+use crate::progress::{self, ProgressRing, TimerBar};
+#[cfg(feature = "image")]
+use crate::image::ImageDisplay;
+#[cfg(feature = "qr")]
+use crate::qr::QrDisplay;
+#[cfg(feature = "scripting")]
+use crate::script::{Script, TickState};
+use crate::server::{self, SharedStatus, TimerStatus};
 use crate::song::SongDisplay;
 
+// How many seconds a single breath of the timer color takes
+const BREATHE_SECS: f64 = 4.0;
+
+// How many milliseconds the timer stays in each color while flashing in the final seconds
+const FLASH_INTERVAL_MS: u128 = 500;
+
+// How many seconds before the next second the timer digits start rolling over
+const DIGIT_ROLL_SECS: f64 = 0.3;
+
+// How many seconds the quit key has to be pressed again in, if quitting has to be confirmed
+const QUIT_CONFIRM_SECS: u64 = 2;
+
+// How often the config file is checked for changes, if hot reloading is enabled
+const CONFIG_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+// How often the countdown is written to the state file, if it is persisted
+const PERSIST_INTERVAL: Duration = Duration::from_secs(1);
+
+// A slot of the vertical layout
+#[derive(Clone, Copy, PartialEq)]
+enum Slot {
+    Clock,                  // The small clock in the top line, if displayed next to the timer
+    TopFill,                // The free space above the content
+    BottomFill,             // The free space below the content
+    Element(LayoutElement), // A displayed element
+}
+
 pub struct App {
     config: Config,                      // The config used for this App
-    chat: Option<TwitchChat>,            // The TwitchChat widget if enabled
+    chat: Option<ChatDisplay>,           // The chat widget if enabled
     song_display: Option<SongDisplay>,   // The current song display widget if enabled
     runtime: Option<Runtime>,            // Tokio runtime used if chat is enabled
+    #[cfg(feature = "scripting")]
+    script: Option<Script>,              // User provided script called every tick
+    #[cfg(feature = "image")]
+    image: Option<ImageDisplay>,         // The image widget if configured
+    #[cfg(feature = "qr")]
+    qr: Option<QrDisplay>,               // The QR code widget if configured
+    launch_time: Instant,                // When the App was created
+    launch_wall_time: SystemTime,        // The system time when the App was created
     start_time: Option<Instant>,         // The start time of the countdown
     original_duration: Option<Duration>, // The original duration of the countdown
     remaining_time: Option<Duration>,    // The remaining time of the countdown
+    pomodoro: Option<Pomodoro>,          // The pomodoro cycles if the timer repeats
+    stopwatch: bool,                     // Whether the elapsed time is counted up instead
+    finished: bool,                      // Whether the countdown has finished
+    confirming: bool,                    // Whether the confirmation screen is shown before the start
+    paused_at: Option<Instant>,          // When the countdown was paused, if it is paused
+    paused_by: Option<String>,           // The user who paused the countdown from chat
+    resume_at: Option<Instant>,          // When the pause from chat runs out
+    events: EventBus,                    // Distributes events to integrations
+    last_tick_secs: Option<Option<u64>>, // Remaining seconds of the last tick event
+    fired_triggers: HashSet<u64>,        // Thresholds of the time triggers which already ran
+    trigger_remaining: Option<Duration>, // The remaining time when the time triggers were last checked
+    blackout: bool,                      // Blank the entire screen if this is true
+    focused: bool,                       // Whether the terminal is focused
+    channel_input: Option<String>,       // The Twitch channel being typed in, if prompted
+    quit_requested: Option<Instant>,     // When the quit key was first pressed, if it has to be confirmed
+    perf: Option<PerfStats>,             // Render loop statistics if perf mode is enabled
+    config_reload: Option<ConfigReload>, // Tracks the config file, if changes are applied while running
+    persisted_at: Option<Instant>,       // When the countdown was last written to the state file
+    serve_port: Option<u16>,             // The port of the status server, if it is enabled
+    status: SharedStatus,                // The countdown snapshot served by the status server
     exit: bool,                          // Exit if this is true
 }
 
@@ -38,9 +116,37 @@ impl Default for App {
             chat: None,
             song_display: None,
             runtime: None,
+            #[cfg(feature = "scripting")]
+            script: None,
+            #[cfg(feature = "image")]
+            image: None,
+            #[cfg(feature = "qr")]
+            qr: None,
+            launch_time: Instant::now(),
+            launch_wall_time: SystemTime::now(),
             start_time: None,
             original_duration: None,
             remaining_time: None,
+            pomodoro: None,
+            stopwatch: false,
+            finished: false,
+            confirming: false,
+            paused_at: None,
+            paused_by: None,
+            resume_at: None,
+            events: EventBus::default(),
+            last_tick_secs: None,
+            fired_triggers: HashSet::new(),
+            trigger_remaining: None,
+            blackout: false,
+            focused: true,
+            channel_input: None,
+            quit_requested: None,
+            perf: None,
+            config_reload: None,
+            persisted_at: None,
+            serve_port: None,
+            status: SharedStatus::default(),
             exit: false,
         }
     }
@@ -51,25 +157,134 @@ impl App {
         self.config = config
     }
 
-    pub fn set_duration(&mut self, duration: Duration) {
+    // Set the countdown duration, the countdown starts ticking after the delay has passed
+    pub fn set_duration(&mut self, duration: Duration, delay: Duration) {
         self.original_duration = Some(duration);
         self.remaining_time = Some(duration);
-        self.start_time = Some(Instant::now());
+        self.start_time = Some(self.now() + delay);
+    }
+
+    // Count the elapsed time up from zero instead of counting down, starting after the delay
+    pub fn set_stopwatch(&mut self, delay: Duration) {
+        self.stopwatch = true;
+        self.start_time = Some(self.now() + delay);
+    }
+
+    // Resume the countdown which was persisted by the last run, returns false if there is none
+    pub fn restore_timer(&mut self) -> bool {
+        let Some(persisted) = PersistedTimer::load() else {
+            return false;
+        };
+
+        let duration = Duration::from_millis(persisted.original_duration_ms);
+        let remaining = match persisted.paused_remaining_ms {
+            Some(remaining_ms) => Duration::from_millis(remaining_ms),
+            None => (UNIX_EPOCH + Duration::from_millis(persisted.end_unix_ms))
+                .duration_since(SystemTime::now())
+                .unwrap_or_default(),
+        };
+
+        // A countdown which finished while brb wasn't running is not resumed
+        if remaining.is_zero() || remaining > duration {
+            PersistedTimer::clear();
+            return false;
+        }
+
+        let now = self.now();
+        let Some(start_time) = now.checked_sub(duration - remaining) else {
+            return false;
+        };
+
+        self.original_duration = Some(duration);
+        self.remaining_time = Some(remaining);
+        self.start_time = Some(start_time);
+
+        if persisted.paused_remaining_ms.is_some() {
+            self.paused_at = Some(now);
+        }
+
+        true
+    }
+
+    // Apply changes to the config file while running, the CLI arguments still override its values
+    pub fn watch_config(&mut self, matches: ArgMatches, channel: Option<String>, clipboard_text: Option<String>) {
+        self.config_reload = Some(ConfigReload {
+            matches,
+            channel,
+            clipboard_text,
+            modified: config_modified(),
+            checked_at: Instant::now(),
+        });
+    }
+
+    // Report the countdown over HTTP on the provided port while running
+    pub fn set_serve_port(&mut self, port: u16) {
+        self.serve_port = Some(port);
+    }
+
+    // Collect render loop statistics while running
+    pub fn enable_perf(&mut self) {
+        self.perf = Some(PerfStats::default());
+    }
+
+    // Get the collected render loop statistics, if perf mode is enabled
+    pub fn get_perf_stats(&self) -> Option<&PerfStats> {
+        self.perf.as_ref()
+    }
+
+    // Use repeating pomodoro phases as the countdown, starting with the first work phase
+    pub fn set_pomodoro(&mut self, pomodoro: Pomodoro, delay: Duration) {
+        self.set_duration(pomodoro.get_duration(), delay);
+        self.pomodoro = Some(pomodoro);
     }
 
     // Run the app
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
         // Initialize the chat
         self.init_chat()?;
 
+        // Start the status server
+        self.init_server()?;
+
         // Initialize the song display
         self.init_song_display();
 
-        // Run start commands
-        execute_commands(self.config.get_start_commands());
+        // Load the user provided script
+        #[cfg(feature = "scripting")]
+        self.init_script()?;
+
+        // Load the image, it is skipped if it can't be loaded
+        #[cfg(feature = "image")]
+        if let Some(path) = self.config.get_image() {
+            self.image = ImageDisplay::load(&path).ok();
+        }
+
+        // Generate the QR code
+        #[cfg(feature = "qr")]
+        if let Some(data) = self.config.get_qr() {
+            self.qr = Some(QrDisplay::new(&data)?);
+        }
+
+        // Let the user confirm the settings first, they may also abort here
+        if self.config.is_confirm_start() && self.start_time.is_some() {
+            self.confirm_start(terminal)?;
+
+            if self.exit {
+                return Ok(());
+            }
+        }
+
+        // Register the event sinks and notify them about the start
+        self.init_events();
+        self.events.emit(AppEvent::TimerStarted {
+            duration: self.original_duration,
+        });
+
+        // Report focus changes, so redraws can be reduced while the terminal is unfocused
+        if self.config.is_pause_when_unfocused() {
+            execute!(stdout(), EnableFocusChange).context("Failed enabling focus reporting")?;
+        }
 
-        // How often the UI should be forcefully redrawn
-        let redraw_rate = Duration::from_millis(100);
         // Last redraw time
         let mut last_tick = Instant::now();
 
@@ -78,76 +293,488 @@ impl App {
             // Handle events such as key events
             self.handle_events()?;
 
+            // How often the UI should be forcefully redrawn, less often while unfocused
+            let redraw_rate = if self.focused {
+                Duration::from_millis(100)
+            } else {
+                Duration::from_secs(1)
+            };
+
             // Current time
             let now = Instant::now();
 
-            // If a redraw should happen
-            if now.duration_since(last_tick) >= redraw_rate {
+            // Whether a redraw should happen
+            let tick = now.duration_since(last_tick) >= redraw_rate;
+
+            if tick {
+                // Apply changes to the config file
+                self.reload_config();
+
                 // Update the time
                 self.update_time();
                 last_tick = now;
 
+                // Write the countdown to the state file
+                self.persist_timer(false);
+
+                // Share the countdown with the status server
+                self.update_status();
+
+                // Notify the event sinks once every second
+                self.emit_tick();
+
                 // Update the current song 
                 if let Some(song_display) = self.song_display.as_mut() {
-                    song_display.poll_song();
+                    if let Some(perf) = self.perf.as_mut() {
+                        perf.record_song_poll();
+                    }
+
+                    if let Some(song) = song_display.poll_song() {
+                        self.events.emit(AppEvent::SongChanged(song));
+                    }
                 }
 
                 // Poll chat messages
                 if let Some(chat) = self.chat.as_mut() {
-                    chat.poll_messages();
+                    let messages = chat.poll_messages();
+
+                    if let Some(perf) = self.perf.as_mut() {
+                        perf.record_messages(messages.len());
+                    }
+
+                    for message in messages {
+                        // Exit if an allowed user sent the exit command
+                        if !self.exit && self.is_exit_command(&message) {
+                            self.exit();
+                        }
+
+                        // Pause or resume the countdown if an allowed user sent a pause command
+                        self.handle_pause_command(&message);
+
+                        // Change the accent color if a viewer redeemed the color reward
+                        self.handle_color_reward(&message);
+
+                        self.events.emit(AppEvent::MessageReceived(message));
+                    }
                 }
 
+                // Call the script and apply its overrides
+                #[cfg(feature = "scripting")]
+                self.tick_script();
+            }
+
+            // Draw the UI, only on ticks while unfocused
+            if tick || self.focused {
+                let draw_start = Instant::now();
+                terminal.draw(|frame| self.draw(frame)).context("Failed drawing UI")?;
+
+                if let Some(perf) = self.perf.as_mut() {
+                    perf.record_draw(draw_start.elapsed());
+                }
             }
+        }
+
+        if self.config.is_pause_when_unfocused() {
+            execute!(stdout(), DisableFocusChange).context("Failed disabling focus reporting")?;
+        }
+
+
+        Ok(())
+    }
+
+    // Show the confirmation screen until a key is pressed or the timeout runs out
+    fn confirm_start<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        let shown_at = Instant::now();
+        let timeout = self.config.get_confirm_start_timeout();
+        self.confirming = true;
 
-            // Draw the UI
+        while self.confirming && shown_at.elapsed() < timeout {
             terminal.draw(|frame| self.draw(frame)).context("Failed drawing UI")?;
+
+            while event::poll(Duration::from_millis(50)).context("Failed polling terminal events")? {
+                if let Event::Key(key_event) = event::read().context("Failed reading key events")? {
+                    // Abort with Esc or the quit key, any other key starts the countdown
+                    if self.config.get_quit_key().matches(&key_event) || key_event.code == KeyCode::Esc {
+                        self.exit();
+                    }
+
+                    self.confirming = false;
+                }
+            }
+        }
+
+        self.confirming = false;
+
+        // The countdown only starts once confirmed, so the time spent here is added to the start
+        if let Some(start_time) = self.start_time.as_mut() {
+            *start_time += shown_at.elapsed();
+        }
+
+        Ok(())
+    }
+
+    // Run the countdown without the TUI, printing the remaining time whenever it changes.
+    // In single line mode the line is updated in place and the bell rings when finished,
+    // otherwise every time is printed on a new line, which works if stdout isn't a terminal.
+    pub fn run_background(&mut self, single_line: bool) -> Result<()> {
+        if self.original_duration.is_none() {
+            bail!("Running in the background requires time arguments");
+        }
+
+        // Start the status server
+        self.init_server()?;
+
+        // Register the event sinks and notify them about the start
+        self.init_events();
+        self.events.emit(AppEvent::TimerStarted {
+            duration: self.original_duration,
+        });
+
+        let mut stdout = stdout();
+        let mut last_line = String::new();
+
+        while !self.finished {
+            self.update_time();
+            self.persist_timer(false);
+            self.update_status();
+            self.emit_tick();
+
+            // Only rewrite the line if the displayed time changed
+            if let Some(remaining) = self.remaining_time {
+                let time = self.format_time(remaining);
+                let line = match &self.pomodoro {
+                    Some(pomodoro) => format!("{} {}", pomodoro.label(), time),
+                    None => time,
+                };
+
+                if line != last_line {
+                    if single_line {
+                        write!(stdout, "\r{}\x1b[K", line)
+                    } else {
+                        writeln!(stdout, "{}", line)
+                    }
+                    .context("Failed writing remaining time")?;
+                    stdout.flush().context("Failed writing remaining time")?;
+                    last_line = line;
+                }
+            }
+
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        // Ring the terminal bell to alert that the time is up
+        if single_line {
+            writeln!(stdout, "\x07").context("Failed writing to the terminal")?;
         }
 
+        self.exit();
 
         Ok(())
     }
 
-    // Initialize the chat
+    // Initialize the chat, an existing chat is kept if its channel didn't change
     fn init_chat(&mut self) -> Result<()> {
-        // If the chat is enabled
-        if self.config.is_chat() {
-            // If a twitch channel was configured
-            if let Some(channel) = self.config.get_twitch_channel() {
-                // Create a new tokio runtime in case chat is enabled
+        // The channel which should be displayed, if the chat is enabled
+        let channel = self.chat_channel().filter(|_| self.config.is_chat());
+
+        // Keep the messages and connection of the current chat if it displays the same channel,
+        // only its settings are updated
+        if let (Some(chat), Some(channel)) = (self.chat.as_mut(), &channel) {
+            if chat.get_channel() == channel {
+                chat.apply_config(&self.config);
+                return Ok(());
+            }
+        }
+
+        // Otherwise the chat is rebuilt, or removed if it was disabled
+        self.chat = None;
+
+        // If a channel was configured
+        if let Some(channel) = channel {
+            let joined_channel = channel.clone();
+
+            // Create a new tokio runtime in case chat is enabled
+            if self.runtime.is_none() {
                 self.runtime = Some(Builder::new_multi_thread().worker_threads(1).enable_all().build().context("Failed initializing async runtime")?);
+            }
 
-                // Create a new Twitch chat widget
-                self.chat = Some(TwitchChat::new(self.config.get_color(), channel));
+            // Create a new chat widget
+            self.chat = Some(ChatDisplay::new(channel));
 
-                // Run the chat on a blocking Tokio task
-                if let Some(chat) = self.chat.as_mut() {
-                    self.runtime.as_ref().unwrap().block_on(async {
-                        // Try starting the chat and return the result, so potential erros can be
-                        // propagated up
-                        chat.start()
-                    }).context("Failed starting the chat")?;
-                }
+            // Run the chat on a blocking Tokio task
+            if let Some(chat) = self.chat.as_mut() {
+                chat.apply_config(&self.config);
+
+                self.runtime.as_ref().unwrap().block_on(async {
+                    // Try starting the chat and return the result, so potential erros can be
+                    // propagated up
+                    chat.start()
+                }).context("Failed starting the chat")?;
+            }
+
+            // Remember the Twitch channel for the next launch
+            if let (ChatChannel::Twitch(name), true) = (joined_channel, self.config.is_remember_channel()) {
+                Config::save_last_channel(&name);
             }
         }
 
         Ok(())
     }
 
+    // Get the configured chat channel, a YouTube live stream takes precedence over Twitch
+    fn chat_channel(&self) -> Option<ChatChannel> {
+        #[cfg(feature = "youtube")]
+        if let Some(video_id) = self.config.get_youtube_video_id() {
+            return Some(ChatChannel::YouTube(video_id));
+        }
+
+        self.config.get_twitch_channel().map(ChatChannel::Twitch)
+    }
+
+    // Start the status server on the tokio runtime, if a port was provided
+    fn init_server(&mut self) -> Result<()> {
+        let Some(port) = self.serve_port else {
+            return Ok(());
+        };
+
+        // Create a new tokio runtime in case chat isn't enabled
+        if self.runtime.is_none() {
+            self.runtime = Some(Builder::new_multi_thread().worker_threads(1).enable_all().build().context("Failed initializing async runtime")?);
+        }
+
+        let runtime = self.runtime.as_ref().unwrap();
+        let address = SocketAddr::new(self.config.get_serve_address(), port);
+
+        // Bind right away, so an unavailable port is reported on start
+        let listener = runtime.block_on(server::bind(address))?;
+        runtime.spawn(server::serve(listener, self.status.clone()));
+
+        self.update_status();
+
+        Ok(())
+    }
+
+    // Update the countdown snapshot which is served by the status server
+    fn update_status(&self) {
+        if self.serve_port.is_none() {
+            return;
+        }
+
+        let status = TimerStatus {
+            remaining: self.remaining_time,
+            total: self.original_duration,
+            percentage: self.time_percentage(),
+            paused: self.paused_at.is_some(),
+        };
+
+        *self.status.lock().unwrap() = status;
+    }
+
     fn init_song_display(&mut self) {
         if self.config.is_song_display() {
-            self.song_display = Some(SongDisplay::new());
+            self.song_display = Some(SongDisplay::new(
+                self.config.get_song_min_display(),
+                self.config.is_song_strip_formatting(),
+                self.config
+                    .get_song_next_command()
+                    .filter(|_| self.config.is_song_show_next()),
+                self.config.get_song_command(),
+                self.config.get_song_player(),
+                self.config.get_song_args(),
+                self.config
+                    .is_song_progress()
+                    .then(|| self.config.get_song_progress_command()),
+            ));
+        }
+    }
+
+    // Register the event sinks
+    fn init_events(&mut self) {
+        // Run the configured commands on start and exit
+        self.events.register(CommandSink::new(
+            self.config.get_start_commands(),
+            self.config.get_exit_commands(),
+            self.config.get_phase_commands(),
+            self.config.get_start_script(),
+            self.config.get_exit_script(),
+            self.config.get_exit_commands_on(),
+        ));
+
+        // Write the remaining time to a file if one was configured
+        if let Some(path) = self.config.get_status_file() {
+            self.events.register(FileSink::new(path));
+        }
+
+        // Log the chat messages to a file if one was configured
+        if let Some(path) = self.config.get_chat_log() {
+            self.events.register(ChatLogSink::new(path));
+        }
+
+        // Send desktop notifications at the configured milestones
+        #[cfg(feature = "notifications")]
+        if let Some(milestones) = self.config.get_notifications() {
+            self.events.register(crate::notify::NotificationSink::new(milestones));
+        }
+    }
+
+    // Emit a tick event, if a second has passed since the last one
+    fn emit_tick(&mut self) {
+        let secs = self.remaining_time.map(|r| r.as_secs());
+
+        if self.last_tick_secs == Some(secs) {
+            return;
+        }
+
+        self.last_tick_secs = Some(secs);
+        self.events.emit(AppEvent::Tick {
+            remaining: self.remaining_time,
+            percent: self.time_percentage(),
+        });
+    }
+
+    // Load the script if one was configured
+    #[cfg(feature = "scripting")]
+    fn init_script(&mut self) -> Result<()> {
+        if let Some(path) = self.config.get_script() {
+            self.script = Some(Script::load(&path)?);
+        }
+
+        Ok(())
+    }
+
+    // Call the "on_tick" function of the script and apply the returned overrides
+    #[cfg(feature = "scripting")]
+    fn tick_script(&mut self) {
+        let state = TickState {
+            remaining: self.remaining_time,
+            percent: self.time_percentage(),
+            messages: self.chat.as_ref().map_or(0, |c| c.get_message_count()),
+        };
+
+        if let Some(script) = self.script.as_mut() {
+            let overrides = script.on_tick(state);
+
+            if let Some(text) = overrides.text {
+                self.config.set_text(text);
+            }
+
+            if let Some(color) = overrides.color {
+                self.config.set_color(color);
+            }
+        }
+    }
+
+    // Reload the config if the file changed since the last check
+    fn reload_config(&mut self) {
+        let Some(reload) = self.config_reload.as_mut() else {
+            return;
+        };
+
+        if reload.checked_at.elapsed() < CONFIG_RELOAD_INTERVAL {
+            return;
+        }
+
+        reload.checked_at = Instant::now();
+
+        let modified = config_modified();
+        if modified == reload.modified {
+            return;
+        }
+
+        reload.modified = modified;
+
+        // An invalid config is ignored, so half finished edits keep the previous config in use
+        let preset = reload.matches.get_one::<Preset>("preset").copied();
+        let Ok(mut config) = Config::load_with_preset(preset) else {
+            return;
+        };
+
+        // Only values provided on the command line are applied again, so the defaults of the CLI
+        // arguments don't replace the values of the file
+        args::apply_matches(&mut config, &reload.matches);
+        config.apply_last_channel();
+
+        if let Some(text) = &reload.clipboard_text {
+            config.set_text(text.clone());
+        }
+
+        // The channel can be changed while running, so the displayed one is kept unless the
+        // channel of the file changed
+        let loaded_channel = config.get_twitch_channel();
+        if loaded_channel == reload.channel {
+            if let Some(channel) = self.config.get_twitch_channel() {
+                config.set_twitch_channel(channel);
+            }
+        }
+
+        reload.channel = loaded_channel;
+
+        // Most values are read while rendering, so they apply right away
+        self.config = config;
+
+        // The chat is only rebuilt if it was toggled or its channel changed, a chat which fails
+        // to start is dropped like an invalid config
+        if self.init_chat().is_err() {
+            self.chat = None;
+        }
+    }
+
+    // Write the countdown to the state file, at most once per interval unless forced.
+    // Pomodoro cycles and the stopwatch are not persisted.
+    fn persist_timer(&mut self, force: bool) {
+        if !self.config.is_persist_timer() || self.finished || self.stopwatch || self.pomodoro.is_some() {
+            return;
+        }
+
+        if !force && self.persisted_at.is_some_and(|persisted_at| persisted_at.elapsed() < PERSIST_INTERVAL) {
+            return;
+        }
+
+        let (Some(duration), Some(start_time)) = (self.original_duration, self.start_time) else {
+            return;
+        };
+
+        self.persisted_at = Some(Instant::now());
+
+        let remaining = duration.saturating_sub(self.elapsed_since(start_time));
+        let end = SystemTime::now() + remaining;
+
+        PersistedTimer {
+            original_duration_ms: duration.as_millis() as u64,
+            end_unix_ms: end.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            paused_remaining_ms: self.paused_at.map(|_| remaining.as_millis() as u64),
         }
+        .save();
     }
 
     // Update the time values
     fn update_time(&mut self) {
+        // Resume the countdown once a pause from chat has run out
+        if self.resume_at.is_some_and(|resume_at| self.now() >= resume_at) {
+            self.resume();
+        }
+
         // If a countdown is set
         if let (Some(duration), Some(start_time)) = (self.original_duration, self.start_time) {
             // How much time has elapsed since the countdown start
-            let elapsed = start_time.elapsed();
+            let elapsed = self.elapsed_since(start_time);
+
+            // Run the time triggers whose threshold was crossed
+            self.check_time_triggers(duration.saturating_sub(elapsed));
 
             // If the countdown has finished
             if elapsed >= duration {
+                // Continue with the next pomodoro phase, if there is one left
+                if self.advance_pomodoro(start_time + duration) {
+                    return;
+                }
+
+                // Only handle the transition once
+                if !self.finished {
+                    self.finished = true;
+                    self.on_finished();
+                }
+
                 // If the the timer is configured to be hidden
                 if self.config.is_hide_timer() {
                     self.remaining_time = None;
@@ -155,19 +782,174 @@ impl App {
                     self.remaining_time = Some(Duration::ZERO);
                 }
             } else {
-                // Otherwise update the remaining time value
-                self.remaining_time = Some(duration - elapsed);
+                // Otherwise update the remaining time value, rounded to whole seconds
+                self.remaining_time = Some(round_duration(duration - elapsed, self.config.get_timer_round()));
             };
         }
     }
 
+    // Run the commands of the time triggers, once the remaining time falls from above their
+    // threshold to or below it. Every trigger only runs once, unless time is added back.
+    fn check_time_triggers(&mut self, remaining: Duration) {
+        let Some(last_remaining) = self.trigger_remaining.replace(remaining) else {
+            return;
+        };
+
+        for (threshold, command) in self.config.get_time_triggers() {
+            if last_remaining > threshold && remaining <= threshold && self.fired_triggers.insert(threshold.as_secs()) {
+                events::execute_commands(&[command]);
+            }
+        }
+    }
+
+    // Start the next pomodoro phase at the provided time, returns false if there is none
+    fn advance_pomodoro(&mut self, phase_start: Instant) -> bool {
+        let Some(pomodoro) = self.pomodoro.as_mut() else {
+            return false;
+        };
+
+        if !pomodoro.advance() {
+            return false;
+        }
+
+        let duration = pomodoro.get_duration();
+        let label = pomodoro.label();
+
+        // The next phase starts exactly when the previous one ended, so no time drifts
+        self.original_duration = Some(duration);
+        self.remaining_time = Some(duration.saturating_sub(self.now().saturating_duration_since(phase_start)));
+        self.start_time = Some(phase_start);
+
+        self.events.emit(AppEvent::PhaseChanged(label));
+
+        // Every pomodoro phase has its own time triggers
+        self.fired_triggers.clear();
+        self.trigger_remaining = None;
+
+        true
+    }
+
+    // Get how much countdown time has passed since the provided instant, which stands still while paused
+    fn elapsed_since(&self, instant: Instant) -> Duration {
+        self.paused_at
+            .unwrap_or_else(|| self.now())
+            .saturating_duration_since(instant)
+    }
+
+    // Get the current time of the countdown clock. With the wall clock, the time which passed
+    // according to the system time is added to the launch, so time spent sleeping is included.
+    fn now(&self) -> Instant {
+        match self.config.get_clock_source() {
+            ClockSource::Monotonic => Instant::now(),
+            ClockSource::Wallclock => match self.launch_wall_time.elapsed() {
+                Ok(elapsed) => self.launch_time + elapsed,
+                // The system time was set back before the launch, so fall back to the monotonic clock
+                Err(_) => Instant::now(),
+            },
+        }
+    }
+
+    // Pause the countdown, unless it already is paused or has finished
+    fn pause(&mut self) {
+        if self.paused_at.is_none() && self.start_time.is_some() && !self.finished {
+            self.paused_at = Some(self.now());
+        }
+    }
+
+    // Resume the paused countdown, where the start is moved back by how long it was paused
+    fn resume(&mut self) {
+        self.paused_by = None;
+        self.resume_at = None;
+
+        let Some(paused_at) = self.paused_at.take() else {
+            return;
+        };
+
+        let paused_for = self.now().saturating_duration_since(paused_at);
+
+        if let Some(start_time) = self.start_time.as_mut() {
+            *start_time += paused_for;
+        }
+    }
+
+    // Pause the countdown, or resume it if it already is paused
+    fn toggle_pause(&mut self) {
+        if self.paused_at.is_some() {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    // Extend the countdown by the configured time step
+    fn add_time(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        if let Some(duration) = self.original_duration.as_mut() {
+            *duration += self.config.get_time_step();
+        }
+
+        self.update_time();
+
+        // The time triggers which are above the remaining time again run once more
+        if let Some(remaining) = self.trigger_remaining {
+            self.fired_triggers.retain(|&secs| Duration::from_secs(secs) >= remaining);
+        }
+    }
+
+    // Shorten the countdown by the configured time step, at most until no time remains
+    fn remove_time(&mut self) {
+        let (Some(duration), Some(start_time), false) = (self.original_duration, self.start_time, self.finished) else {
+            return;
+        };
+
+        let elapsed = self.elapsed_since(start_time);
+        self.original_duration = Some(duration.saturating_sub(self.config.get_time_step()).max(elapsed));
+
+        self.update_time();
+    }
+
+    // Skip to the next pomodoro phase right away, or finish the countdown on the last one
+    fn skip_phase(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        if !self.advance_pomodoro(self.now()) {
+            // Move the start back, so the countdown is finished by the next update
+            if let Some(start_time) = self.original_duration.and_then(|d| self.now().checked_sub(d)) {
+                self.start_time = Some(start_time);
+            }
+        }
+
+        self.update_time();
+    }
+
+    // Called once when the countdown finishes
+    fn on_finished(&mut self) {
+        self.events.emit(AppEvent::Finished);
+
+        // A finished countdown is not resumed on the next start
+        if self.config.is_persist_timer() {
+            PersistedTimer::clear();
+        }
+
+        // Play the finished sound if one was configured
+        #[cfg(feature = "sound")]
+        if let Some(path) = self.config.get_finished_sound() {
+            crate::sound::play(path);
+        }
+    }
+
     // Caluclate how much of the time has elapsed in percent
     fn time_percentage(&self) -> Option<u16> {
         // If a countdown is set
         if let (Some(start_time), Some(original_duration)) =
             (&self.start_time, &self.original_duration)
         {
-            let elapsed = start_time.elapsed().as_secs_f64();
+            let elapsed = self.elapsed_since(*start_time).as_secs_f64();
             let total = original_duration.as_secs_f64();
             Some(((elapsed / total) * 100.0).min(100.0) as u16)
         } else {
@@ -176,6 +958,177 @@ impl App {
         }
     }
 
+    // Calculate how much of the progress should be filled in percent, which drains if inverted
+    fn progress_percentage(&self) -> Option<u16> {
+        self.time_percentage().map(|percentage| {
+            if self.config.is_progress_invert() {
+                100 - percentage
+            } else {
+                percentage
+            }
+        })
+    }
+
+    // Check if the countdown is in its final seconds, or has already finished
+    fn is_near_end(&self) -> bool {
+        self.remaining_time
+            .is_some_and(|remaining| remaining <= self.config.get_alert_threshold())
+    }
+
+    // Get the color of the timer and progress, which slowly lightens and darkens if breathing
+    fn timer_color(&self) -> Color {
+        let color = self.config.get_color();
+
+        // A paused countdown is dimmed, so it is obvious that the time stands still
+        if self.paused_at.is_some() {
+            return progress::reduce_color(progress::scale_brightness(color, 0.4), self.config.get_color_depth());
+        }
+
+        if !self.config.is_breathe() {
+            return color;
+        }
+
+        // A full breath takes a few seconds, the brightness varies between 60% and 100%
+        let phase = self.launch_time.elapsed().as_secs_f64() / BREATHE_SECS * std::f64::consts::TAU;
+        let color = progress::scale_brightness(color, 0.8 + 0.2 * phase.sin());
+
+        progress::reduce_color(color, self.config.get_color_depth())
+    }
+
+    // Get the color of the timer digits, which flash brighter every other half-second in the final seconds
+    fn digits_color(&self) -> Color {
+        let color = self.timer_color();
+        let flash = self.config.get_countdown_flash();
+
+        let flashing = !flash.is_zero()
+            && !self.stopwatch
+            && !self.finished
+            && self.paused_at.is_none()
+            && self.remaining_time.is_some_and(|remaining| remaining <= flash);
+
+        if !flashing || (self.launch_time.elapsed().as_millis() / FLASH_INTERVAL_MS).is_multiple_of(2) {
+            return color;
+        }
+
+        // Move every channel halfway towards white, a white timer is dimmed instead
+        let (r, g, b) = progress::to_rgb(color);
+        let brighten = |c: u8| c + (255 - c) / 2;
+
+        let flash_color = if (r, g, b) == (255, 255, 255) {
+            progress::scale_brightness(color, 0.5)
+        } else {
+            Color::Rgb(brighten(r), brighten(g), brighten(b))
+        };
+
+        progress::reduce_color(flash_color, self.config.get_color_depth())
+    }
+
+    // Get the next displayed time and how far the digits have rolled towards it, if animated
+    fn digit_roll(&self) -> Option<(Duration, f64)> {
+        if !self.config.is_digit_animation()
+            || self.config.is_timer_words()
+            || self.config.is_timer_decimal()
+            || self.config.get_timer_font() != TimerFont::Block
+            || self.config.get_timer_size() != BigTextSize::Full
+            || self.paused_at.is_some()
+        {
+            return None;
+        }
+
+        let (Some(duration), Some(start_time), Some(remaining)) = (self.original_duration, self.start_time, self.remaining_time) else {
+            return None;
+        };
+
+        let secs = remaining.as_secs();
+        if secs == 0 {
+            return None;
+        }
+
+        // The exact remaining time at which the displayed seconds change
+        let boundary = match self.config.get_timer_round() {
+            TimerRound::Floor => secs as f64,
+            TimerRound::Ceil => secs as f64 - 1.0,
+            TimerRound::Round => secs as f64 - 0.5,
+        };
+
+        let exact = duration.saturating_sub(self.elapsed_since(start_time)).as_secs_f64();
+        let progress = 1.0 - (exact - boundary) / DIGIT_ROLL_SECS;
+
+        Some((Duration::from_secs(secs - 1), progress))
+    }
+
+    // Get the time which the timer displays, the elapsed time in stopwatch mode
+    fn displayed_time(&self) -> Option<Duration> {
+        match (self.stopwatch, self.start_time) {
+            (true, Some(start_time)) => Some(round_duration(self.elapsed_since(start_time), TimerRound::Floor)),
+            _ => self.remaining_time,
+        }
+    }
+
+    // Format the time as configured, either like a clock or in decimal hours
+    fn format_time(&self, duration: Duration) -> String {
+        let decimal_places = self.config.is_timer_decimal().then(|| self.config.get_timer_decimal_places());
+        format_duration(duration, self.config.is_pad_minutes(), decimal_places)
+    }
+
+    // Render the summary of the planned break, shown before the countdown starts
+    fn render_confirmation(&self, area: Rect, buf: &mut Buffer) {
+        let duration = self.original_duration.map(|duration| self.format_time(duration)).unwrap_or_default();
+
+        let mut lines = vec![
+            Line::from("Break starting").bold().fg(self.config.get_color()),
+            Line::from(""),
+            Line::from(duration),
+        ];
+
+        lines.extend(self.current_text().lines().map(|line| Line::from(line.to_string())));
+        lines.push(Line::from(""));
+        lines.push(Line::from("Press any key or wait, Esc to abort").add_modifier(Modifier::DIM));
+
+        // Center the summary vertically
+        let height = (lines.len() as u16).min(area.height);
+        let summary_area = Rect {
+            y: area.y + (area.height - height) / 2,
+            height,
+            ..area
+        };
+
+        Paragraph::new(lines).centered().render(summary_area, buf);
+    }
+
+    // Get the text which should currently be displayed
+    fn current_text(&self) -> String {
+        // Show who paused the countdown from chat
+        if let Some(user) = &self.paused_by {
+            return format!("Paused by {}", user);
+        }
+
+        // Announce the end of the countdown if a finished text was configured
+        if let (Some(finished_text), true) = (self.config.get_finished_text(), self.finished) {
+            return finished_text;
+        }
+
+        // Show the active phase while the pomodoro is running
+        if let (Some(pomodoro), false) = (&self.pomodoro, self.finished) {
+            return pomodoro.label();
+        }
+
+        let rotating_text = self.config.get_rotating_text();
+        let interval = self.config.get_text_rotate_interval().as_secs();
+
+        // Fall back to the configured text if there are no texts to rotate through
+        if rotating_text.is_empty() || interval == 0 {
+            return rotating_text
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| self.config.get_text());
+        }
+
+        // Advance to the next text every interval
+        let index = (self.launch_time.elapsed().as_secs() / interval) as usize % rotating_text.len();
+        rotating_text[index].clone()
+    }
+
     // Draw the App
     fn draw(&self, frame: &mut Frame) {
         frame.render_widget(self, frame.area());
@@ -184,8 +1137,11 @@ impl App {
     // Handle events
     fn handle_events(&mut self) -> Result<()> {
         while event::poll(Duration::from_millis(50)).context("Failed polling terminal events")? {
-            if let Event::Key(key_event) = event::read().context("Failed reading key events")? {
-                self.handle_key_event(key_event);
+            match event::read().context("Failed reading key events")? {
+                Event::Key(key_event) => self.handle_key_event(key_event),
+                Event::FocusGained => self.focused = true,
+                Event::FocusLost => self.focused = false,
+                _ => {}
             }
         }
         Ok(())
@@ -193,15 +1149,133 @@ impl App {
 
     // Specifically handle key input events
     fn handle_key_event(&mut self, key_event: KeyEvent) {
-        if let KeyCode::Char('q') = key_event.code {
-            self.exit();
+        // If a channel is being typed in, the keys are used for the input instead
+        if self.channel_input.is_some() {
+            self.handle_channel_input(key_event);
+            return;
+        }
+
+        if self.config.get_quit_key().matches(&key_event) {
+            // The first press only asks for confirmation, a second press in time actually quits
+            if self.config.is_confirm_quit() && !self.is_quit_requested() {
+                self.quit_requested = Some(Instant::now());
+            } else {
+                self.exit();
+            }
+
+            return;
+        }
+
+        match key_event.code {
+            // Toggle the blackout screen
+            KeyCode::Char('b') => self.blackout = !self.blackout,
+            // Freeze or continue the countdown
+            KeyCode::Char(' ') => self.toggle_pause(),
+            // Add time to or remove time from the countdown
+            KeyCode::Char('+') => self.add_time(),
+            KeyCode::Char('-') => self.remove_time(),
+            // Prompt for a new Twitch channel if the chat is displayed
+            KeyCode::Char('c') if self.chat.is_some() => self.channel_input = Some(String::new()),
+            // Skip to the next pomodoro phase
+            KeyCode::Char(c) if c == self.config.get_next_phase_key() && self.pomodoro.is_some() => self.skip_phase(),
+            // Scroll through the chat history
+            KeyCode::Up => if let Some(chat) = self.chat.as_mut() { chat.scroll_up() },
+            KeyCode::Down => if let Some(chat) = self.chat.as_mut() { chat.scroll_down() },
+            KeyCode::End => if let Some(chat) = self.chat.as_mut() { chat.scroll_to_bottom() },
+            _ => {}
+        }
+    }
+
+    // Check if the quit key was pressed recently enough, so pressing it again quits
+    fn is_quit_requested(&self) -> bool {
+        self.quit_requested
+            .is_some_and(|requested| requested.elapsed() < Duration::from_secs(QUIT_CONFIRM_SECS))
+    }
+
+    // Handle key input events while prompting for a new Twitch channel
+    fn handle_channel_input(&mut self, key_event: KeyEvent) {
+        let Some(input) = self.channel_input.as_mut() else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let channel = input.trim().to_lowercase();
+
+                // Switch to the entered channel, unless nothing was entered
+                if let Some(chat) = self.chat.as_mut() {
+                    if !channel.is_empty() && chat.change_channel(channel.clone()).is_ok() {
+                        // Remember the channel for the next launch
+                        if self.config.is_remember_channel() {
+                            Config::save_last_channel(&channel);
+                        }
+
+                        self.config.set_twitch_channel(channel);
+                    }
+                }
+
+                self.channel_input = None;
+            }
+            KeyCode::Esc => self.channel_input = None,
+            _ => {}
+        }
+    }
+
+    // Check if a chat message is the exit command sent by an allowed user
+    fn is_exit_command(&self, message: &ChatMessage) -> bool {
+        is_chat_command(
+            message,
+            self.config.get_chat_exit_command(),
+            &self.config.get_chat_exit_users(),
+            self.config.is_chat_exit_allow_mods(),
+        )
+    }
+
+    // Change the accent color to the text of the configured channel-point reward, invalid
+    // colors are ignored
+    fn handle_color_reward(&mut self, message: &ChatMessage) {
+        let Some(reward_id) = self.config.get_chat_color_reward() else {
+            return;
+        };
+
+        if message.reward_id.as_ref() != Some(&reward_id) {
+            return;
+        }
+
+        if let Ok(color) = args::color_arg_parser(message.message.trim()) {
+            self.config.set_color(color);
+        }
+    }
+
+    // Pause the countdown for a while or resume it, if an allowed user sent the matching command
+    fn handle_pause_command(&mut self, message: &ChatMessage) {
+        let users = self.config.get_chat_pause_users();
+        let allow_mods = self.config.is_chat_pause_allow_mods();
+
+        if is_chat_command(message, self.config.get_chat_pause_command(), &users, allow_mods) {
+            self.pause();
+
+            // Only a countdown which is actually paused resumes automatically
+            if self.paused_at.is_some() {
+                self.paused_by = Some(message.sender.clone());
+                self.resume_at = Some(self.now() + self.config.get_chat_pause_duration());
+            }
+        } else if is_chat_command(message, self.config.get_chat_resume_command(), &users, allow_mods) {
+            self.resume();
         }
     }
 
     // Exit the App
     fn exit(&mut self) {
-        // Run exit commands before finishing the program
-        execute_commands(self.config.get_exit_commands());
+        // Notify the event sinks before finishing the program
+        self.events.emit(AppEvent::Exited);
+
+        // Store the latest state of an unfinished countdown, so it can be resumed
+        self.persist_timer(true);
 
         // Stop the song display
         if let Some(mut s) = self.song_display.take() {
@@ -215,13 +1289,55 @@ impl App {
 // Implement Widget for the App so it can be rendered
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        // If the blackout is active, only render a blank screen
+        if self.blackout {
+            Clear.render(area, buf);
+            return;
+        }
+
+        // Before the countdown starts, only the summary of the settings is displayed
+        if self.confirming {
+            self.render_confirmation(area, buf);
+            return;
+        }
+
         let outer_block = Block::new().borders(Borders::NONE).padding(Padding::uniform(self.config.get_padding()));
 
         let inner_area = outer_block.inner(area);
 
         // Split the text which should be displayed into multiple lines at newline characters
-        let text = self.config.get_text();
-        let text_lines: Vec<Line> = text.split('\n').map(Line::from).collect();
+        let text = self.current_text();
+
+        // The time shown by the timer, counting down or up
+        let displayed_time = self.displayed_time();
+
+        // The time of day, which is displayed big in place of the timer if there is no countdown
+        let clock = self.config.is_show_clock().then(|| Local::now().format("%H:%M").to_string());
+        let standalone_clock = clock.as_ref().filter(|_| displayed_time.is_none());
+
+        // An empty text is hidden as well, so its space can be used by the other elements
+        let hide_text = self.config.is_hide_text() || text.trim().is_empty();
+
+        let text_lines: Vec<Line> = if hide_text {
+            // If the text is hidden, no lines are displayed, so no space is reserved for it
+            Vec::new()
+        } else {
+            let lines: Vec<&str> = text.split('\n').collect();
+
+            // Remove blank lines around the text, so no space is wasted on them. Blank lines in
+            // between are kept, since they are most likely intentional spacing.
+            let (start, end) = if self.config.is_trim_text() {
+                let is_content = |line: &&str| !line.trim().is_empty();
+                (
+                    lines.iter().position(is_content).unwrap_or(0),
+                    lines.iter().rposition(is_content).map_or(lines.len(), |index| index + 1),
+                )
+            } else {
+                (0, lines.len())
+            };
+
+            lines[start..end].iter().copied().map(Line::from).collect()
+        };
 
         // Layout constraints for horizontally aligned widgets
         let horizontal_constraints =
@@ -248,127 +1364,543 @@ impl Widget for &App {
             .constraints(horizontal_constraints)
             .split(inner_area);
 
-        // Layout constraints for horizontally aligned widgets
-        let vertical_constraints = 
-        // If there is a remaining time we reserve some space for the countdown
-        if self.remaining_time.is_some() {
-            vec![
-                Constraint::Fill(1),
-                Constraint::Max(8),
-                Constraint::Max(4 * text_lines.len() as u16), // Enough space for all text lines
-                Constraint::Fill(1),
-                Constraint::Max(3),
-            ]
-        } else { // Otherwise we allow the other elements to use this space
-            vec![
-                Constraint::Fill(1), 
-                Constraint::Max(4 * text_lines.len() as u16), // Enough space for all text lines
-                Constraint::Fill(1),
-            ]
+        // Whether the timer is displayed as a compact bar, which also displays the progress
+        let timer_bar = self.config.get_timer_style() == TimerStyle::Bar;
+
+        // Whether the progress is displayed as a ring around the timer instead of a bar
+        let progress_ring = self.config.is_progress_bar()
+            && self.config.get_progress_style() == ProgressStyle::Ring
+            && !timer_bar;
+
+        // How the free space above and below the content is distributed
+        let (top_fill, bottom_fill) = match self.config.get_content_valign() {
+            VerticalAlign::Top => (1, 4),
+            VerticalAlign::Center => (1, 1),
+            VerticalAlign::Bottom => (4, 1),
+        };
+
+        // The Twitch channel which is displayed as a callout if the chat is off
+        let channel_callout = self
+            .config
+            .get_twitch_channel()
+            .filter(|_| self.config.is_show_channel() && self.chat.is_none());
+
+        // Whether the song should be displayed at the bottom, below everything else
+        let song_at_bottom = self.song_display.is_some()
+            && self.config.get_song_position() == SongPosition::Bottom;
+
+        // The slots of the vertical layout, either in the configured order or the default one
+        let layout = self.config.get_layout();
+        let slots = match &layout {
+            Some(elements) => {
+                let mut slots = vec![Slot::Clock, Slot::TopFill];
+                slots.extend(elements.iter().map(|e| Slot::Element(*e)));
+                slots.push(Slot::BottomFill);
+                slots
+            }
+            None => {
+                let mut slots = vec![
+                    Slot::Clock,
+                    Slot::TopFill,
+                    Slot::Element(LayoutElement::Timer),
+                    Slot::Element(LayoutElement::Text),
+                    Slot::BottomFill,
+                    Slot::Element(LayoutElement::Progress),
+                    Slot::Element(LayoutElement::Channel),
+                ];
+
+                if song_at_bottom {
+                    slots.push(Slot::Element(LayoutElement::Song));
+                }
+
+                slots
+            }
         };
 
+        // The configured sizes of the big timer digits and the text, seven-segment digits always fit
+        let (timer_width, timer_height) = self.config.get_timer_size().char_size();
+        let timer_height = if self.config.get_timer_font() == TimerFont::Block { timer_height } else { 8 };
+        let text_height = self.config.get_text_size().char_size().1;
+
+        // Reserve space for every slot, elements which aren't displayed don't get a slot at all
+        let slots: Vec<(Slot, Constraint)> = slots
+            .into_iter()
+            .filter_map(|slot| {
+                let constraint = match slot {
+                    // The clock is only small if the timer is displayed as well
+                    Slot::Clock => (clock.is_some() && standalone_clock.is_none()).then_some(Constraint::Length(1)),
+                    Slot::TopFill => Some(Constraint::Fill(top_fill)),
+                    Slot::BottomFill => Some(Constraint::Fill(bottom_fill)),
+                    Slot::Element(LayoutElement::Timer) => (displayed_time.is_some() || standalone_clock.is_some()).then_some(
+                        Constraint::Max(match (timer_bar, progress_ring) {
+                            (true, _) => 3, // The bar only needs a single line with some space around it
+                            (_, true) => timer_height + 2, // Extra space for the ring
+                            _ => timer_height,
+                        }),
+                    ),
+                    // Enough space for all text lines
+                    Slot::Element(LayoutElement::Text) => Some(Constraint::Max(text_height * text_lines.len() as u16)),
+                    // The bar already shows the progress
+                    Slot::Element(LayoutElement::Progress) => self.remaining_time.filter(|_| !timer_bar).map(|_| Constraint::Max(3)),
+                    Slot::Element(LayoutElement::Channel) => channel_callout.as_ref().map(|_| Constraint::Max(1)),
+                    Slot::Element(LayoutElement::Song) => self.song_display.as_ref().map(|song| Constraint::Max(song.height())),
+                };
+
+                constraint.map(|c| (slot, c))
+            })
+            .collect();
+
         // Split a part of the horizontal layout based on the constraints
         let vertical_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vertical_constraints)
+            .constraints(slots.iter().map(|(_, c)| *c))
             .split(horizontal_layout[1]);
 
-        // If some time is remaining on the countdown
-        if let Some(duration) = &self.remaining_time {
-            // Format the time nicely
-            let time_str = format_duration(*duration);
+        // Get the area of a slot, or None if it isn't displayed
+        let slot_area = |slot: Slot| {
+            slots
+                .iter()
+                .position(|(s, _)| *s == slot)
+                .map(|index| vertical_layout[index])
+        };
+
+        // The free space above the content, which is always present
+        let top_area = slot_area(Slot::TopFill).unwrap_or_default();
 
-            // Display it using the BigText widget
-            let time_display = BigText::builder()
-                .pixel_size(PixelSize::Full)
-                .style(Style::new().fg(self.config.get_color()))
-                .lines(vec![time_str.into()])
-                .centered()
-                .build();
+        // Without a configured layout, a song at the top is displayed in the space above the content
+        let song_in_top_area = layout.is_none() && self.song_display.is_some() && !song_at_bottom;
+
+        // If some time is remaining on the countdown, or elapsed on the stopwatch
+        if let (Some(duration), Some(timer_area)) = (&displayed_time, slot_area(Slot::Element(LayoutElement::Timer))) {
+            // If the timer should be displayed as a compact bar
+            if timer_bar {
+                let time_str = self.format_time(*duration);
+                let percentage = self.progress_percentage().unwrap_or(0);
+
+                TimerBar::new(time_str, percentage, self.digits_color())
+                    .render(timer_area, buf);
+            } else {
+                // Display the time using the BigText widget, either as words or nicely formatted.
+                // The width of the displayed time in cells is needed to fit the progress ring around it.
+                let (time_display, time_width) = if self.config.is_timer_words() {
+                    // Every time unit is displayed on its own line using the smaller text style
+                    let time_words = format_duration_words(*duration);
+                    let time_width = time_words.iter().map(|w| w.chars().count()).max().unwrap_or(0) * 4;
+                    let time_lines: Vec<Line> = time_words.into_iter().map(Line::from).collect();
+
+                    let time_display = BigText::builder()
+                        .pixel_size(PixelSize::Quadrant)
+                        .style(Style::new().fg(self.digits_color()))
+                        .lines(time_lines)
+                        .centered()
+                        .build();
+
+                    (Some(time_display), time_width)
+                } else if self.config.get_timer_font() == TimerFont::SevenSegment {
+                    // The seven-segment digits are rendered separately below
+                    let time_str = self.format_time(*duration);
+                    let time_width = SevenSegment::new(time_str, self.timer_color()).width() as usize;
+
+                    (None, time_width)
+                } else {
+                    let time_str = self.format_time(*duration);
+                    let time_width = time_str.chars().count() * timer_width as usize;
+
+                    let time_display = BigText::builder()
+                        .pixel_size(self.config.get_timer_size().pixel_size())
+                        .style(Style::new().fg(self.digits_color()))
+                        .lines(vec![time_str.into()])
+                        .centered()
+                        .build();
+
+                    (Some(time_display), time_width)
+                };
+
+                let mut time_area = timer_area;
+
+                // If the progress should be displayed as a ring around the timer
+                if let (Some(percentage), true) = (self.progress_percentage(), progress_ring) {
+                    // Fit the ring tightly around the time, leaving a cell of space on each side
+                    let ring_width = (time_width as u16 + 4).min(time_area.width);
+                    let ring_area = Rect {
+                        x: time_area.x + (time_area.width - ring_width) / 2,
+                        width: ring_width,
+                        ..time_area
+                    };
+
+                    ProgressRing::new(percentage, self.timer_color()).render(ring_area, buf);
+
+                    // Render the time inside the ring
+                    time_area = ring_area.inner(Margin::new(2, 1));
+                }
+
+                // And finally render it at the correct position inside the vertical layout,
+                // rolling the digits over to the next second if animated
+                if let Some((next, progress)) = self.digit_roll() {
+                    RollingDigits::new(
+                        self.format_time(*duration),
+                        self.format_time(next),
+                        progress,
+                        self.digits_color(),
+                    )
+                    .render(time_area, buf);
+                } else if let Some(time_display) = time_display {
+                    time_display.render(time_area, buf);
+                } else {
+                    SevenSegment::new(self.format_time(*duration), self.digits_color())
+                        .render(time_area, buf);
+                }
+
+                // If we have a completion percentage and a slot for the progress
+                if let (Some(percentage), Some(progress_area)) = (&self.progress_percentage(), slot_area(Slot::Element(LayoutElement::Progress))) {
+                    // And if the progress bar is enabled, unless it is hidden in the final seconds
+                    if self.config.is_progress_bar() && !progress_ring && !(self.config.is_progress_hide_near_end() && self.is_near_end()) {
+                        // The unfilled part uses the terminal background, unless a color was configured
+                        let mut gauge_style = Style::new().fg(self.timer_color());
+                        if let Some(bg) = self.config.get_progress_bg() {
+                            gauge_style = gauge_style.bg(bg);
+                        }
 
-            // And finally render it at the correct position inside the vertical layout
-            let time_area = vertical_layout[1];
-            time_display.render(time_area, buf);
-
-            // If we have a completion percentage
-            if let Some(percentage) = &self.time_percentage() {
-                // And if the progress bar is enabled
-                if self.config.is_progress_bar() {
-                    // Create a new "Gauge" widget
-                    let progress_display = Gauge::default()
-                        .block(
-                            Block::default()
-                                .borders(Borders::NONE)
-                                .padding(Padding::uniform(1)),
-                        )
-                        .gauge_style(Style::new().fg(self.config.get_color()))
-                        .use_unicode(true)
-                        .percent(*percentage);
-
-                    // And render it
-                    let progress_area = vertical_layout[4];
-                    progress_display.render(progress_area, buf);
+                        // Create a new "Gauge" widget
+                        let progress_display = Gauge::default()
+                            .block(
+                                Block::default()
+                                    .borders(Borders::NONE)
+                                    .padding(Padding::uniform(1)),
+                            )
+                            .gauge_style(gauge_style)
+                            .use_unicode(true)
+                            .percent(*percentage);
+
+                        // And render it
+                        progress_display.render(progress_area, buf);
+                    }
                 }
             }
         }
 
-        // Create a BigText widget for the text
-        let text_display = BigText::builder()
-            .pixel_size(PixelSize::Quadrant)
-            .style(Style::new().white())
-            .lines(text_lines)
-            .centered()
-            .build();
+        // Without a countdown, the clock is displayed like the timer
+        if let (Some(clock), None, Some(timer_area)) = (standalone_clock, displayed_time, slot_area(Slot::Element(LayoutElement::Timer))) {
+            BigText::builder()
+                .pixel_size(self.config.get_timer_size().pixel_size())
+                .style(Style::new().fg(self.timer_color()))
+                .lines(vec![clock.clone().into()])
+                .centered()
+                .build()
+                .render(timer_area, buf);
+        }
 
-        // And render it in the correct position depending on if the time is displayed
-        let text_area = if self.remaining_time.is_some() {
-            vertical_layout[2]
-        } else {
-            vertical_layout[1]
-        };
+        // If the text is not hidden and has a slot
+        if let (false, Some(text_area)) = (hide_text, slot_area(Slot::Element(LayoutElement::Text))) {
+            // Create a BigText widget for the text
+            let text_display = BigText::builder()
+                .pixel_size(self.config.get_text_size().pixel_size())
+                .style(Style::new().white())
+                .lines(text_lines)
+                .centered()
+                .build();
+
+            // And render it in its slot
+            text_display.render(text_area, buf);
+        }
 
-        text_display.render(text_area, buf);
+        // If we have an image, render it in the space above the timer
+        #[cfg(feature = "image")]
+        if let Some(image) = &self.image {
+            let mut image_area = top_area;
+
+            // Leave some space for the song if it is displayed at the top
+            if let (true, Some(song)) = (song_in_top_area, &self.song_display) {
+                image_area.y += song.height().min(image_area.height);
+                image_area.height = image_area.height.saturating_sub(song.height());
+            }
+
+            image.render(image_area, buf);
+        }
 
-        // If we have song display, render it
+        // If we have a QR code, render it in the space below the text
+        #[cfg(feature = "qr")]
+        if let Some(qr) = &self.qr {
+            qr.render(slot_area(Slot::BottomFill).unwrap_or_default(), buf);
+        }
+
+        // If the channel callout is displayed, render it below the content
+        if let (Some(channel), Some(channel_area)) = (channel_callout, slot_area(Slot::Element(LayoutElement::Channel))) {
+            Line::from(format!("twitch.tv/{}", channel))
+                .style(Style::new().fg(self.config.get_color()).bold())
+                .centered()
+                .render(channel_area, buf);
+        }
+
+        // If we have song display, render it at the configured position
         if let Some(song_text) = &self.song_display {
-            song_text.render(vertical_layout[0], buf);
+            let song_area = slot_area(Slot::Element(LayoutElement::Song))
+                .or(song_in_top_area.then_some(top_area));
+
+            if let Some(song_area) = song_area {
+                song_text.render(song_area, buf);
+            }
         }
 
         // If we have a chat, render it
         if let Some(chat) = &self.chat {
-            chat.render(horizontal_layout[2], buf);
+            let mut chat_area = horizontal_layout[2];
+
+            // If the chat is wider than the configured maximum, cap it aligned to the right edge
+            if let Some(max_width) = self.config.get_chat_max_width() {
+                if chat_area.width > max_width {
+                    chat_area.x = chat_area.right() - max_width;
+                    chat_area.width = max_width;
+                }
+            }
+
+            chat.render(chat_area, buf);
+
+            // If a new channel is being typed in, render the prompt over the bottom of the chat
+            if let Some(input) = &self.channel_input {
+                let prompt_height = chat_area.height.min(3);
+                let prompt_area = Rect {
+                    y: chat_area.bottom() - prompt_height,
+                    height: prompt_height,
+                    ..chat_area
+                };
+
+                let prompt = Paragraph::new(format!("{}_", input)).block(
+                    Block::default()
+                        .title(" channel ")
+                        .border_type(BorderType::Rounded)
+                        .borders(Borders::ALL)
+                        .border_style(Style::new().fg(self.config.get_color())),
+                );
+
+                Clear.render(prompt_area, buf);
+                prompt.render(prompt_area, buf);
+            }
         }
 
         outer_block.render(area, buf);
+
+        // Next to a countdown, the clock is displayed small in the top line
+        if let (Some(clock), Some(clock_area)) = (&clock, slot_area(Slot::Clock)) {
+            Line::from(clock.as_str()).bold().centered().render(clock_area, buf);
+        }
+
+        // Display the version and how long brb has been running in the bottom line, if configured
+        if self.config.is_show_footer() && area.height > 0 {
+            let uptime = self.launch_time.elapsed().as_secs();
+            let footer_area = Rect {
+                y: area.bottom() - 1,
+                height: 1,
+                ..horizontal_layout[1]
+            };
+
+            Line::from(format!(
+                "brb v{} · up {}:{:02}:{:02}",
+                env!("CARGO_PKG_VERSION"),
+                uptime / 3600,
+                uptime % 3600 / 60,
+                uptime % 60
+            ))
+            .style(Style::new().add_modifier(Modifier::DIM))
+            .centered()
+            .render(footer_area, buf);
+        }
+
+        // Ask to press the quit key again, until the confirmation runs out
+        if self.is_quit_requested() {
+            let message = format!("Press {} again to quit", self.config.get_quit_key().name());
+            let width = (message.chars().count() as u16 + 4).min(area.width);
+            let height = area.height.min(3);
+            let confirm_area = Rect {
+                x: area.x + (area.width - width) / 2,
+                y: area.y + (area.height - height) / 2,
+                width,
+                height,
+            };
+
+            let confirm = Paragraph::new(message).centered().block(
+                Block::default()
+                    .border_type(BorderType::Rounded)
+                    .borders(Borders::ALL)
+                    .border_style(Style::new().fg(self.config.get_color())),
+            );
+
+            Clear.render(confirm_area, buf);
+            confirm.render(confirm_area, buf);
+        }
+
+        // Dim everything once the countdown has finished, if configured
+        if self.finished && self.config.is_dim_on_finish() {
+            buf.set_style(area, Style::new().add_modifier(Modifier::DIM));
+        }
+    }
+}
+
+// Check if a chat message is the provided command sent by an allowed user
+fn is_chat_command(message: &ChatMessage, command: Option<String>, allowed_users: &[String], allow_mods: bool) -> bool {
+    let Some(command) = command else {
+        return false;
+    };
+
+    if message.message.trim() != command {
+        return false;
+    }
+
+    // Only allow moderators if configured, or users on the allowlist
+    (message.privileged && allow_mods)
+        || allowed_users
+            .iter()
+            .any(|user| user.eq_ignore_ascii_case(&message.sender))
+}
+
+// The countdown as it is stored in the state file, so it can be resumed after a restart
+#[derive(Serialize, Deserialize)]
+struct PersistedTimer {
+    original_duration_ms: u64,        // The original duration of the countdown
+    end_unix_ms: u64,                 // When the countdown ends as a Unix timestamp
+    paused_remaining_ms: Option<u64>, // The remaining time, if the countdown is paused
+}
+
+impl PersistedTimer {
+    // Load the persisted countdown, or None if it is missing or invalid
+    fn load() -> Option<Self> {
+        let content = fs::read_to_string(Config::get_timer_state_file().ok()?).ok()?;
+        toml::from_str(&content).ok()
+    }
+
+    // Write the countdown to the state file, errors are ignored as this is only a convenience
+    fn save(&self) {
+        let (Ok(path), Ok(content)) = (Config::get_timer_state_file(), toml::to_string(self)) else {
+            return;
+        };
+
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+
+        let _ = fs::write(path, content);
+    }
+
+    // Remove the state file, so no countdown is resumed
+    fn clear() {
+        if let Ok(path) = Config::get_timer_state_file() {
+            let _ = fs::remove_file(path);
+        }
     }
 }
 
-// Helper function for formatting the time
-fn format_duration(duration: Duration) -> String {
+// What is needed to reload the config file while running
+struct ConfigReload {
+    matches: ArgMatches, // The CLI arguments, which override the reloaded values
+    channel: Option<String>, // The Twitch channel of the last loaded config
+    clipboard_text: Option<String>, // The clipboard contents read on start, used as the text
+    modified: Option<SystemTime>, // When the config file was last modified
+    checked_at: Instant, // When the config file was last checked for changes
+}
+
+// Get when the config file was last modified, or None if it doesn't exist
+fn config_modified() -> Option<SystemTime> {
+    let path = Config::get_config_dir().ok()?;
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// Helper function for rounding a duration to whole seconds
+fn round_duration(duration: Duration, round: TimerRound) -> Duration {
+    let secs = match round {
+        TimerRound::Floor => duration.as_secs(),
+        TimerRound::Ceil => duration.as_secs_f64().ceil() as u64,
+        TimerRound::Round => duration.as_secs_f64().round() as u64,
+    };
+
+    Duration::from_secs(secs)
+}
+
+// Helper function for formatting the time, optionally zero-padding the minutes
+fn format_duration(duration: Duration, pad_minutes: bool, decimal_places: Option<usize>) -> String {
+    // Display the time in decimal hours instead, like "0.25h"
+    if let Some(places) = decimal_places {
+        return format!("{:.*}h", places, duration.as_secs_f64() / 3600.0);
+    }
+
     let secs = duration.as_secs();
     let mins = secs / 60;
     let secs = secs % 60;
-    format!("{:02}:{:02}", mins, secs)
+
+    if pad_minutes {
+        format!("{:02}:{:02}", mins, secs)
+    } else {
+        format!("{}:{:02}", mins, secs)
+    }
 }
 
+// Helper function for formatting the time as words, one line per time unit
+fn format_duration_words(duration: Duration) -> Vec<String> {
+    let secs = duration.as_secs();
 
-// Execute commands in the background
-fn execute_commands(commands: Vec<String>) {
-    for command in commands {
-        let parts = Shlex::new(&command).collect::<Vec<String>>();
-        if let Some(first) = parts.first() {
-            let mut c = Command::new(first);
+    // Only display the seconds during the last minute
+    if secs < 60 {
+        return vec![unit_words(secs, "second")];
+    }
 
-            // Don't output anything, as this would mess with the TUI
-            c.stdin(Stdio::null());
-            c.stdout(Stdio::null());
-            c.stderr(Stdio::null());
+    // Otherwise round up to full minutes, so "4:59" is displayed as "five minutes"
+    let total_mins = secs.div_ceil(60);
+    let hours = total_mins / 60;
+    let mins = total_mins % 60;
 
-            c.args(&parts[1..]);
+    let mut lines = Vec::new();
 
-            // Also ignore the Result in case the command is not found,
-            // as this would mess with the TUI
-            let _ = c.spawn();
-        }
+    if hours > 0 {
+        lines.push(unit_words(hours, "hour"));
+    }
+
+    if mins > 0 {
+        lines.push(unit_words(mins, "minute"));
+    }
+
+    lines
+}
+
+// Helper function for formatting an amount of a unit as words, like "five minutes"
+fn unit_words(amount: u64, unit: &str) -> String {
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {}{}", number_words(amount), unit, plural)
+}
+
+// Helper function for converting a number to words, numbers above 99 are kept as digits
+fn number_words(number: u64) -> String {
+    const ONES: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    match number {
+        0..=19 => ONES[number as usize].to_string(),
+        20..=99 if number.is_multiple_of(10) => TENS[number as usize / 10].to_string(),
+        20..=99 => format!("{}-{}", TENS[number as usize / 10], ONES[number as usize % 10]),
+        _ => number.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_minutes() {
+        assert_eq!(format_duration(Duration::from_secs(299), true, None), "04:59");
+        assert_eq!(format_duration(Duration::from_secs(5), true, None), "00:05");
+    }
+
+    #[test]
+    fn unpadded_minutes() {
+        assert_eq!(format_duration(Duration::from_secs(299), false, None), "4:59");
+        assert_eq!(format_duration(Duration::from_secs(5), false, None), "0:05");
+        assert_eq!(format_duration(Duration::from_secs(754), false, None), "12:34");
     }
 }
@@ -0,0 +1,181 @@
+use anyhow::{bail, Context, Result};
+use ratatui::style::Color;
+use serde_json::{json, Value};
+use std::{thread, time::Duration};
+use tokio::sync::mpsc;
+use ureq::Agent;
+
+use crate::chat::{ChatBadge, ChatMessage, ChatSource};
+
+// The live chat page of a stream, which contains the first continuation token
+const LIVE_CHAT_URL: &str = "https://www.youtube.com/live_chat";
+
+// The endpoint which returns the messages received after a continuation token
+const GET_LIVE_CHAT_URL: &str =
+    "https://www.youtube.com/youtubei/v1/live_chat/get_live_chat?prettyPrint=false";
+
+// YouTube only serves the full live chat page to browsers
+const USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+
+// The web client version which is used if the page doesn't contain one
+const DEFAULT_CLIENT_VERSION: &str = "2.20240101.00.00";
+
+// How long to wait before polling again, if YouTube doesn't tell or the request failed
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+// A client which reads the live chat of a YouTube stream, without requiring an API key
+pub struct YouTubeClient {
+    tx: mpsc::Sender<ChatMessage>, // The sender used for sending back new messages
+}
+
+impl YouTubeClient {
+    // Create a new client where the provided Sender is used to send back new messages
+    pub fn new(tx: mpsc::Sender<ChatMessage>) -> Self {
+        Self { tx }
+    }
+}
+
+impl ChatSource for YouTubeClient {
+    // Start polling the live chat of the provided video
+    fn start(&mut self, video_id: String) -> Result<()> {
+        let agent = ureq::AgentBuilder::new().user_agent(USER_AGENT).build();
+
+        // Load the page right away, so a stream without a live chat is reported on start
+        let (client_version, continuation) = fetch_live_chat_page(&agent, &video_id)?;
+
+        let tx = self.tx.clone();
+        thread::spawn(move || poll_live_chat(agent, client_version, continuation, tx));
+
+        Ok(())
+    }
+
+    // The polling is bound to the continuation of the video, so it can't be changed
+    fn change_channel(&mut self, _video_id: String) -> Result<()> {
+        bail!("Changing the YouTube live stream is not supported")
+    }
+}
+
+// Load the live chat page of a video, returning the client version and the first continuation
+fn fetch_live_chat_page(agent: &Agent, video_id: &str) -> Result<(String, String)> {
+    let page = agent
+        .get(LIVE_CHAT_URL)
+        .query("is_popout", "1")
+        .query("v", video_id)
+        .call()
+        .context("Failed loading the YouTube live chat")?
+        .into_string()
+        .context("Failed reading the YouTube live chat")?;
+
+    let client_version = find_string_value(&page, "clientVersion")
+        .unwrap_or_else(|| DEFAULT_CLIENT_VERSION.to_string());
+    let continuation = find_string_value(&page, "continuation")
+        .context("The YouTube video has no live chat, is it currently streaming?")?;
+
+    Ok((client_version, continuation))
+}
+
+// Find the first string value of a key in the JSON which is embedded in the page
+fn find_string_value(page: &str, key: &str) -> Option<String> {
+    let pattern = format!("\"{}\":\"", key);
+    let start = page.find(&pattern)? + pattern.len();
+    let length = page[start..].find('"')?;
+
+    Some(page[start..start + length].to_string())
+}
+
+// Poll the live chat until the stream ends or the chat is closed
+fn poll_live_chat(
+    agent: Agent,
+    client_version: String,
+    mut continuation: String,
+    tx: mpsc::Sender<ChatMessage>,
+) {
+    loop {
+        let body = json!({
+            "context": { "client": { "clientName": "WEB", "clientVersion": client_version } },
+            "continuation": continuation,
+        });
+
+        // Failed requests are retried, the continuation stays valid for a while
+        let Some(response) = agent
+            .post(GET_LIVE_CHAT_URL)
+            .send_json(body)
+            .ok()
+            .and_then(|response| response.into_json::<Value>().ok())
+        else {
+            thread::sleep(DEFAULT_POLL_INTERVAL);
+            continue;
+        };
+
+        let live_chat = &response["continuationContents"]["liveChatContinuation"];
+
+        for action in live_chat["actions"].as_array().into_iter().flatten() {
+            let renderer = &action["addChatItemAction"]["item"]["liveChatTextMessageRenderer"];
+
+            if let Some(message) = parse_message(renderer) {
+                // Stop polling once the chat was closed
+                if tx.blocking_send(message).is_err() {
+                    return;
+                }
+            }
+        }
+
+        // The next continuation is missing once the stream has ended
+        let Some(next) = live_chat["continuations"][0]
+            .as_object()
+            .and_then(|data| data.values().next())
+        else {
+            return;
+        };
+
+        let Some(next_continuation) = next["continuation"].as_str() else {
+            return;
+        };
+
+        continuation = next_continuation.to_string();
+
+        let interval = next["timeoutMs"]
+            .as_u64()
+            .map_or(DEFAULT_POLL_INTERVAL, Duration::from_millis);
+        thread::sleep(interval);
+    }
+}
+
+// Turn a text message of the live chat into a ChatMessage, emojis are written as their shortcut
+fn parse_message(renderer: &Value) -> Option<ChatMessage> {
+    let sender = renderer["authorName"]["simpleText"].as_str()?.to_string();
+
+    let message = renderer["message"]["runs"]
+        .as_array()?
+        .iter()
+        .filter_map(|run| {
+            run["text"]
+                .as_str()
+                .or_else(|| run["emoji"]["shortcuts"][0].as_str())
+        })
+        .collect();
+
+    // The owner of the stream is displayed like a Twitch broadcaster
+    let badge = renderer["authorBadges"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|badge| {
+            match badge["liveChatAuthorBadgeRenderer"]["icon"]["iconType"].as_str() {
+                Some("OWNER") => Some(ChatBadge::Broadcaster),
+                Some("MODERATOR") => Some(ChatBadge::Moderator),
+                _ => None,
+            }
+        })
+        .min_by_key(|badge| *badge != ChatBadge::Broadcaster);
+
+    // YouTube has no name colors, so every sender is displayed like Twitch users without one
+    Some(ChatMessage::new(
+        Color::Rgb(255, 255, 255),
+        sender,
+        message,
+        badge,
+        None,
+    ))
+}